@@ -0,0 +1,40 @@
+use std::io::Error as IoError;
+use std::sync::Arc;
+
+use tempfile::TempDir;
+use tide::multipart::Limits;
+use tide::prelude::*;
+use tide::Request;
+
+#[async_std::main]
+async fn main() -> Result<(), IoError> {
+    femme::start();
+    let dir = Arc::new(tempfile::tempdir()?);
+    let mut app = tide::with_state(dir);
+    app.with(tide::log::LogMiddleware::new());
+
+    // To test this example:
+    // $ cargo run --example multipart --features multipart
+    // $ curl -F description=readme -F file=@README.md localhost:8080
+    app.at("/")
+        .post(|mut req: Request<Arc<TempDir>>| async move {
+            let mut form = req
+                .body_multipart()?
+                .into_form(Limits::new().file_size(1024 * 1024))
+                .await?;
+
+            let description = form.field("description").unwrap_or_default().to_owned();
+            let mut saved = vec![];
+            while let Some(file) = form.take_file("file") {
+                let name = format!("upload-{}", saved.len());
+                let size = file.size();
+                file.persist(req.state().path().join(&name))?;
+                saved.push(json!({ "name": name, "bytes": size }));
+            }
+
+            Ok(json!({ "description": description, "files": saved }))
+        });
+
+    app.listen("127.0.0.1:8080").await?;
+    Ok(())
+}
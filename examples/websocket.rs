@@ -0,0 +1,22 @@
+use async_std::prelude::*;
+use tide::websocket::{Message, WebSocket};
+
+#[async_std::main]
+async fn main() -> Result<(), std::io::Error> {
+    femme::start();
+    let mut app = tide::new();
+    app.with(tide::log::LogMiddleware::new());
+    app.at("/echo")
+        .get(WebSocket::new(|_req, mut conn| async move {
+            while let Some(message) = conn.next().await {
+                match message? {
+                    Message::Text(text) => conn.send_string(text).await?,
+                    Message::Binary(bytes) => conn.send_bytes(bytes).await?,
+                    _ => {}
+                }
+            }
+            Ok(())
+        }));
+    app.listen("localhost:8080").await?;
+    Ok(())
+}
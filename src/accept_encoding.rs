@@ -0,0 +1,43 @@
+//! `Accept-Encoding` negotiation.
+
+/// Pick the content coding in `supported` with the highest quality value in
+/// an `Accept-Encoding` header, preferring earlier entries on ties.
+///
+/// Unlike `http_types::content::AcceptEncoding`, this honours `q=0` and `*`.
+pub(crate) fn negotiate<'a>(header: &str, supported: &[&'a str]) -> Option<&'a str> {
+    let mut explicit = Vec::new();
+    let mut wildcard = None;
+    for item in header.split(',') {
+        let mut params = item.split(';');
+        let coding = params
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let quality = params
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        match coding.as_str() {
+            "*" => wildcard = Some(quality),
+            "x-gzip" => explicit.push(("gzip".to_owned(), quality)),
+            _ => explicit.push((coding, quality)),
+        }
+    }
+
+    let mut best = None;
+    let mut best_quality = 0.0;
+    for &coding in supported {
+        let quality = explicit
+            .iter()
+            .find(|(name, _)| name == coding)
+            .map(|(_, quality)| *quality)
+            .or(wildcard)
+            .unwrap_or(0.0);
+        if quality > best_quality {
+            best = Some(coding);
+            best_quality = quality;
+        }
+    }
+    best
+}
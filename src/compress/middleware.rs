@@ -0,0 +1,238 @@
+use async_compression::futures::bufread;
+use async_std::io::BufReader;
+
+use crate::accept_encoding;
+use crate::http::headers::{
+    HeaderValue, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH, ETAG, VARY,
+};
+use crate::http::{Body, Method, StatusCode};
+use crate::{Middleware, Next, Request, Response};
+
+/// The default minimum body size to compress, in bytes.
+const DEFAULT_THRESHOLD: usize = 1024;
+
+/// Compress response bodies according to the request's `Accept-Encoding`.
+///
+/// Bodies are compressed as they are streamed, without buffering them first.
+/// Responses are left untouched if they:
+///
+/// - already have a `Content-Encoding`,
+/// - have a known length below the [threshold](CompressMiddleware::threshold),
+/// - are Server-Sent Event streams,
+/// - have `Cache-Control: no-transform`,
+/// - are partial (`206`), empty (`204`, `304`) or answer a `HEAD` request.
+///
+/// Compressed responses get a `Content-Encoding` header, and any strong
+/// `ETag` is turned into a weak one. All responses that could have been
+/// compressed get `Vary: Accept-Encoding`.
+///
+/// # Examples
+///
+/// ```
+/// let mut app = tide::new();
+/// app.with(tide::compress::CompressMiddleware::new());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompressMiddleware {
+    threshold: usize,
+}
+
+impl Default for CompressMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompressMiddleware {
+    /// Create a new instance of `CompressMiddleware`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Set the minimum body size in bytes for a response to be compressed.
+    ///
+    /// Bodies of unknown length are always compressed. Defaults to 1024.
+    #[must_use]
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Whether `res` could be compressed at all, regardless of what the
+    /// client accepts.
+    fn is_compressible(&self, res: &Response) -> bool {
+        if matches!(
+            res.status(),
+            StatusCode::NoContent | StatusCode::PartialContent | StatusCode::NotModified
+        ) || res.status().is_informational()
+        {
+            return false;
+        }
+
+        if res.header(CONTENT_ENCODING).is_some() {
+            return false;
+        }
+
+        if let Some(cache_control) = res.header(CACHE_CONTROL) {
+            let no_transform = cache_control
+                .iter()
+                .flat_map(|value| value.as_str().split(','))
+                .any(|directive| directive.trim().eq_ignore_ascii_case("no-transform"));
+            if no_transform {
+                return false;
+            }
+        }
+
+        if let Some(mime) = res.content_type() {
+            if mime.essence() == "text/event-stream" {
+                return false;
+            }
+        }
+
+        match res.len() {
+            Some(len) => len >= self.threshold,
+            None => true,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for CompressMiddleware {
+    async fn handle(&self, req: Request<State>, next: Next<'_, State>) -> crate::Result {
+        let is_head = req.method() == Method::Head;
+        let encoding = req
+            .header(ACCEPT_ENCODING)
+            .and_then(|values| Encoding::negotiate(values.as_str()));
+
+        let mut res = next.run(req).await;
+        if is_head || !self.is_compressible(&res) {
+            return Ok(res);
+        }
+
+        add_vary(&mut res);
+        if let Some(encoding) = encoding {
+            compress(&mut res, encoding);
+        }
+        Ok(res)
+    }
+}
+
+/// Add `Accept-Encoding` to the response's `Vary` header.
+fn add_vary(res: &mut Response) {
+    let varies = res
+        .header(VARY)
+        .into_iter()
+        .flat_map(|values| values.iter())
+        .flat_map(|value| value.as_str().split(','))
+        .map(str::trim)
+        .any(|name| name == "*" || name.eq_ignore_ascii_case(ACCEPT_ENCODING.as_str()));
+    if !varies {
+        res.append_header(VARY, "Accept-Encoding");
+    }
+}
+
+fn compress(res: &mut Response, encoding: Encoding) {
+    let body = res.take_body();
+    let mime = body.mime().clone();
+    let mut body = Body::from_reader(BufReader::new(encoding.encode(body)), None);
+    body.set_mime(mime);
+    res.set_body(body);
+
+    res.remove_header(CONTENT_LENGTH);
+    res.insert_header(CONTENT_ENCODING, encoding.as_str());
+
+    // The compressed body is no longer byte-for-byte identical to the one the
+    // ETag was computed for.
+    if let Some(etag) = res
+        .header(ETAG)
+        .map(|values| values.last().as_str().to_owned())
+    {
+        if !etag.starts_with("W/") {
+            let weak: HeaderValue = format!("W/{}", etag).parse().unwrap();
+            res.insert_header(ETAG, weak);
+        }
+    }
+}
+
+/// A content coding this middleware can apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    #[cfg(feature = "compress-brotli")]
+    Brotli,
+    #[cfg(feature = "compress-zstd")]
+    Zstd,
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// All supported encodings, in order of preference.
+    const ALL: &'static [Encoding] = &[
+        #[cfg(feature = "compress-brotli")]
+        Encoding::Brotli,
+        #[cfg(feature = "compress-zstd")]
+        Encoding::Zstd,
+        Encoding::Gzip,
+        Encoding::Deflate,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            #[cfg(feature = "compress-brotli")]
+            Encoding::Brotli => "br",
+            #[cfg(feature = "compress-zstd")]
+            Encoding::Zstd => "zstd",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    /// Pick the encoding with the highest quality value in an
+    /// `Accept-Encoding` header, preferring earlier entries of
+    /// [`Encoding::ALL`] on ties.
+    fn negotiate(header: &str) -> Option<Self> {
+        let names: Vec<_> = Self::ALL.iter().map(|encoding| encoding.as_str()).collect();
+        let name = accept_encoding::negotiate(header, &names)?;
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|encoding| encoding.as_str() == name)
+    }
+
+    fn encode(self, body: Body) -> Box<dyn async_std::io::Read + Unpin + Send + Sync> {
+        match self {
+            #[cfg(feature = "compress-brotli")]
+            Encoding::Brotli => Box::new(bufread::BrotliEncoder::new(body)),
+            #[cfg(feature = "compress-zstd")]
+            Encoding::Zstd => Box::new(bufread::ZstdEncoder::new(body)),
+            Encoding::Gzip => Box::new(bufread::GzipEncoder::new(body)),
+            Encoding::Deflate => Box::new(bufread::ZlibEncoder::new(body)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Encoding;
+
+    #[test]
+    fn negotiate() {
+        assert_eq!(Encoding::negotiate("gzip"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::negotiate("deflate, gzip"), Some(Encoding::Gzip));
+        assert_eq!(
+            Encoding::negotiate("gzip;q=0.5, deflate"),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(Encoding::negotiate("x-gzip"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::negotiate("gzip;q=0, deflate;q=0"), None);
+        assert_eq!(Encoding::negotiate("identity"), None);
+        assert_eq!(Encoding::negotiate("*;q=0"), None);
+        assert_eq!(
+            Encoding::negotiate("*;q=0.5, gzip;q=0, deflate"),
+            Some(Encoding::Deflate)
+        );
+    }
+}
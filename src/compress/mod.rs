@@ -0,0 +1,8 @@
+//! Response compression; see [`CompressMiddleware`].
+//!
+//! gzip and deflate are always available. brotli and zstd are enabled with
+//! the `compress-brotli` and `compress-zstd` features respectively.
+
+mod middleware;
+
+pub use middleware::CompressMiddleware;
@@ -0,0 +1,338 @@
+//! Conditional requests.
+//!
+//! [`ConditionalGet`] answers `If-None-Match` and `If-Modified-Since` with
+//! `304 Not Modified`, and failed `If-Match` and `If-Unmodified-Since`
+//! preconditions with `412 Precondition Failed`. Endpoints can set their own
+//! validators with [`ResponseBuilder::etag`](crate::ResponseBuilder::etag)
+//! and [`ResponseBuilder::last_modified`](crate::ResponseBuilder::last_modified);
+//! otherwise the middleware computes an `ETag` from the response body.
+//!
+//! Endpoints that modify state should check preconditions *before* making
+//! any changes, using [`check_preconditions`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use async_std::task::block_on;
+//! # fn main() -> Result<(), std::io::Error> { block_on(async {
+//! #
+//! use std::time::SystemTime;
+//! use tide::conditional::ConditionalGet;
+//! use tide::http::conditional::ETag;
+//! use tide::Response;
+//!
+//! let mut app = tide::new();
+//! app.with(ConditionalGet::new());
+//! app.at("/").get(|_| async { Ok("hashed by the middleware") });
+//! app.at("/versioned").get(|_| async {
+//!     Ok(Response::builder(200)
+//!         .etag(ETag::new("v1".into()))
+//!         .last_modified(SystemTime::UNIX_EPOCH)
+//!         .body("validators set by the endpoint"))
+//! });
+//! app.listen("127.0.0.1:8080").await?;
+//! #
+//! # Ok(()) }) }
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::http::conditional::{
+    ETag, IfMatch, IfModifiedSince, IfNoneMatch, IfUnmodifiedSince, LastModified,
+};
+use crate::http::headers::{
+    HeaderName, CACHE_CONTROL, CONTENT_LOCATION, DATE, ETAG, EXPIRES, LAST_MODIFIED, VARY,
+};
+use crate::http::{Body, Method, StatusCode};
+use crate::{Middleware, Next, Request, Response};
+
+/// The default maximum body size to compute an `ETag` for, in bytes.
+const DEFAULT_MAX_SIZE: usize = 1024 * 1024;
+
+/// Headers kept on a `304 Not Modified` response.
+const NOT_MODIFIED_HEADERS: [HeaderName; 7] = [
+    CACHE_CONTROL,
+    CONTENT_LOCATION,
+    DATE,
+    ETAG,
+    EXPIRES,
+    LAST_MODIFIED,
+    VARY,
+];
+
+/// Middleware answering conditional `GET` and `HEAD` requests.
+///
+/// Responses without an `ETag` or `Last-Modified` header get an `ETag`
+/// computed from their body, as long as its length is known and at most
+/// [`max_size`](ConditionalGet::max_size) bytes. The body is buffered to do
+/// so.
+///
+/// Requests with other methods are passed through untouched, because the
+/// endpoint will already have run by the time the middleware sees its
+/// response. Such endpoints should call [`check_preconditions`] themselves.
+///
+/// # Examples
+///
+/// ```
+/// let mut app = tide::new();
+/// app.with(tide::conditional::ConditionalGet::new().weak(true));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConditionalGet {
+    weak: bool,
+    max_size: usize,
+}
+
+impl Default for ConditionalGet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConditionalGet {
+    /// Create a new instance of `ConditionalGet`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            weak: false,
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+
+    /// Compute weak instead of strong `ETag`s.
+    ///
+    /// Weak `ETag`s survive changes to the body that keep it semantically
+    /// equivalent, such as compression. Defaults to `false`.
+    #[must_use]
+    pub fn weak(mut self, weak: bool) -> Self {
+        self.weak = weak;
+        self
+    }
+
+    /// Set the maximum body size in bytes to compute an `ETag` for.
+    ///
+    /// Defaults to 1 MiB.
+    #[must_use]
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Compute an `ETag` for `res` from its body, if it is small enough.
+    async fn add_etag(&self, res: &mut Response) -> crate::Result<()> {
+        if !res.status().is_success()
+            || res.header(ETAG).is_some()
+            || res.header(LAST_MODIFIED).is_some()
+            || !res.len().into_iter().any(|len| len <= self.max_size)
+        {
+            return Ok(());
+        }
+
+        let body = res.take_body();
+        let mime = body.mime().clone();
+        let bytes = body.into_bytes().await?;
+
+        let tag = format!("{:x}-{:016x}", bytes.len(), fnv1a(&bytes));
+        let etag = if self.weak {
+            ETag::new_weak(tag)
+        } else {
+            ETag::new(tag)
+        };
+        etag.apply(&mut *res);
+
+        let mut body = Body::from(bytes);
+        body.set_mime(mime);
+        res.set_body(body);
+        Ok(())
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`.
+///
+/// Unlike the hashers of the standard library, its output is specified, so
+/// `ETag`s stay the same across builds and deployments.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[async_trait::async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for ConditionalGet {
+    async fn handle(&self, req: Request<State>, next: Next<'_, State>) -> crate::Result {
+        let conditions = Conditions::new(&req);
+        let mut res = next.run(req).await;
+        if !conditions.safe {
+            return Ok(res);
+        }
+        self.add_etag(&mut res).await?;
+        Ok(conditions.respond(res))
+    }
+}
+
+/// Check the preconditions of a state-changing request against the current
+/// validators of the resource it targets.
+///
+/// Pass `None` for both validators if the resource does not exist yet. This
+/// way `If-None-Match: *` can be used to only create, and `If-Match: *` to
+/// only update a resource.
+///
+/// # Errors
+///
+/// A `412 Precondition Failed` error is returned if any of the request's
+/// `If-Match`, `If-Unmodified-Since` or `If-None-Match` preconditions fail.
+///
+/// # Examples
+///
+/// ```
+/// use tide::conditional::check_preconditions;
+/// use tide::http::conditional::ETag;
+///
+/// let mut app = tide::new();
+/// app.at("/doc").put(|mut req: tide::Request<()>| async move {
+///     let current = ETag::new("v1".into());
+///     check_preconditions(&req, Some(&current), None)?;
+///     let doc = req.body_string().await?;
+///     // ...store the new document...
+///     Ok(tide::StatusCode::NoContent)
+/// });
+/// ```
+pub fn check_preconditions<State>(
+    req: &Request<State>,
+    etag: Option<&ETag>,
+    last_modified: Option<SystemTime>,
+) -> crate::Result<()> {
+    // HTTP dates only have a precision of whole seconds.
+    let last_modified = last_modified.map(|time| {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs())
+    });
+    match Conditions::new(req).evaluate(etag, last_modified) {
+        Some(StatusCode::PreconditionFailed) => Err(crate::Error::from_str(
+            StatusCode::PreconditionFailed,
+            "Precondition Failed",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The conditional headers of a request.
+///
+/// Headers that fail to parse are ignored.
+#[derive(Debug)]
+pub(crate) struct Conditions {
+    safe: bool,
+    if_match: Option<IfMatch>,
+    if_unmodified_since: Option<SystemTime>,
+    if_none_match: Option<IfNoneMatch>,
+    if_modified_since: Option<SystemTime>,
+}
+
+impl Conditions {
+    pub(crate) fn new<State>(req: &Request<State>) -> Self {
+        Self {
+            safe: matches!(req.method(), Method::Get | Method::Head),
+            if_match: IfMatch::from_headers(req).ok().flatten(),
+            if_unmodified_since: IfUnmodifiedSince::from_headers(req)
+                .ok()
+                .flatten()
+                .map(|header| header.modified()),
+            if_none_match: IfNoneMatch::from_headers(req).ok().flatten(),
+            if_modified_since: IfModifiedSince::from_headers(req)
+                .ok()
+                .flatten()
+                .map(|header| header.modified()),
+        }
+    }
+
+    /// Evaluate the conditions in the order of RFC 7232, section 6, returning
+    /// the status to respond with instead if any of them fail.
+    pub(crate) fn evaluate(
+        &self,
+        etag: Option<&ETag>,
+        last_modified: Option<SystemTime>,
+    ) -> Option<StatusCode> {
+        let exists = etag.is_some() || last_modified.is_some();
+
+        if let Some(if_match) = &self.if_match {
+            let matches = if if_match.wildcard() {
+                exists
+            } else {
+                etag.into_iter()
+                    .any(|etag| if_match.iter().any(|tag| strong_eq(tag, etag)))
+            };
+            if !matches {
+                return Some(StatusCode::PreconditionFailed);
+            }
+        } else if let (Some(since), Some(modified)) = (self.if_unmodified_since, last_modified) {
+            if modified > since {
+                return Some(StatusCode::PreconditionFailed);
+            }
+        }
+
+        if let Some(if_none_match) = &self.if_none_match {
+            let matches = if if_none_match.wildcard() {
+                exists
+            } else {
+                etag.into_iter()
+                    .any(|etag| if_none_match.iter().any(|tag| weak_eq(tag, etag)))
+            };
+            if matches {
+                return Some(if self.safe {
+                    StatusCode::NotModified
+                } else {
+                    StatusCode::PreconditionFailed
+                });
+            }
+        } else if let (true, Some(since), Some(modified)) =
+            (self.safe, self.if_modified_since, last_modified)
+        {
+            if modified <= since {
+                return Some(StatusCode::NotModified);
+            }
+        }
+
+        None
+    }
+
+    /// Evaluate the conditions against the validators of `res`, replacing it
+    /// with a `304` or `412` response if needed.
+    pub(crate) fn respond(&self, res: Response) -> Response {
+        if !res.status().is_success() {
+            return res;
+        }
+
+        let etag = ETag::from_headers(&res).ok().flatten();
+        let last_modified = LastModified::from_headers(&res)
+            .ok()
+            .flatten()
+            .map(|header| header.modified());
+        match self.evaluate(etag.as_ref(), last_modified) {
+            Some(StatusCode::NotModified) => {
+                let mut not_modified = Response::new(StatusCode::NotModified);
+                for name in &NOT_MODIFIED_HEADERS {
+                    if let Some(values) = res.header(name) {
+                        not_modified.insert_header(name, values);
+                    }
+                }
+                not_modified
+            }
+            Some(status) => Response::new(status),
+            None => res,
+        }
+    }
+}
+
+/// Strong comparison: both tags are strong and identical.
+fn strong_eq(a: &ETag, b: &ETag) -> bool {
+    matches!((a, b), (ETag::Strong(a), ETag::Strong(b)) if a == b)
+}
+
+/// Weak comparison: both tags are identical, ignoring their weakness.
+fn weak_eq(a: &ETag, b: &ETag) -> bool {
+    let (ETag::Strong(a) | ETag::Weak(a)) = a;
+    let (ETag::Strong(b) | ETag::Weak(b)) = b;
+    a == b
+}
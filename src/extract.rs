@@ -0,0 +1,423 @@
+//! Typed extractors for endpoint functions.
+//!
+//! Instead of taking a [`Request`] and pulling values out of it by hand, an
+//! endpoint can take any number of arguments implementing [`FromRequest`]
+//! and be wrapped in [`handler`]. Each argument is extracted from the request
+//! in order, and if any of them fails to extract, the endpoint is not called
+//! and the extraction error is returned instead.
+//!
+//! | Extractor   | Source                        | Status on failure      |
+//! |-------------|-------------------------------|------------------------|
+//! | [`Json`]    | `application/json` body       | 415, 400, 413 or 422   |
+//! | [`Form`]    | urlencoded form body          | 415, 400, 413 or 422   |
+//! | [`Query`]   | URL query string              | 400                    |
+//! | [`Path`]    | route parameters              | 400                    |
+//! | [`State`]   | application state             | never fails            |
+//! | [`Header`]  | a typed request header        | 400                    |
+//! | [`Ext`]     | a request extension           | 500                    |
+//!
+//! Wrapping an extractor in `Option` turns extraction failures into `None`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use async_std::task::block_on;
+//! # fn main() -> Result<(), std::io::Error> { block_on(async {
+//! #
+//! use tide::extract::{handler, Json, Path};
+//! use tide::http::convert::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Cat {
+//!     name: String,
+//! }
+//!
+//! let mut app = tide::new();
+//! app.at("/cats/:id").put(handler(
+//!     |Path(id): Path<u64>, Json(cat): Json<Cat>| async move {
+//!         Ok(format!("cat {} is now called {}", id, cat.name))
+//!     },
+//! ));
+//! app.listen("127.0.0.1:8080").await?;
+//! #
+//! # Ok(()) }) }
+//! ```
+
+use async_std::future::Future;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::http::headers::{self, HeaderName, Headers};
+use crate::http::{self, StatusCode};
+use crate::{Endpoint, Request, Response};
+
+/// Types that can be created from a request.
+///
+/// Extractors take the request by mutable reference so that they can consume
+/// the body, which means only one body extractor can succeed per request.
+#[async_trait]
+pub trait FromRequest<State>: Sized {
+    /// Extract `Self` from the request.
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self>;
+}
+
+#[async_trait]
+impl<State, T> FromRequest<State> for Option<T>
+where
+    State: Send + Sync + 'static,
+    T: FromRequest<State>,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        Ok(T::from_request(req).await.ok())
+    }
+}
+
+#[async_trait]
+impl<State, T> FromRequest<State> for crate::Result<T>
+where
+    State: Send + Sync + 'static,
+    T: FromRequest<State>,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        Ok(T::from_request(req).await)
+    }
+}
+
+macro_rules! extractor {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name<T>(pub T);
+
+        impl<T> $name<T> {
+            /// Consume the extractor, returning the inner value.
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+    };
+}
+
+extractor! {
+    /// Deserializes a JSON request body.
+    ///
+    /// Responds with `415 Unsupported Media Type` if the request's content
+    /// type is not JSON, `400 Bad Request` if the body cannot be read or is
+    /// not valid JSON, `413 Payload Too Large` if it exceeds a
+    /// [`BodyLimit`](crate::security::BodyLimit), and
+    /// `422 Unprocessable Entity` if it does not match `T`.
+    Json
+}
+
+extractor! {
+    /// Deserializes an `application/x-www-form-urlencoded` request body.
+    ///
+    /// Responds with `415 Unsupported Media Type` if the request's content
+    /// type is not a urlencoded form, `400 Bad Request` if the body cannot be
+    /// read, `413 Payload Too Large` if it exceeds a
+    /// [`BodyLimit`](crate::security::BodyLimit), and
+    /// `422 Unprocessable Entity` if it does not match `T`.
+    Form
+}
+
+extractor! {
+    /// Deserializes the URL query string.
+    ///
+    /// Responds with `400 Bad Request` if the query does not match `T`.
+    Query
+}
+
+extractor! {
+    /// Deserializes route parameters.
+    ///
+    /// `T` can be a struct or map, in which case parameters are matched by
+    /// name; a tuple, in which case they are matched by position; or any
+    /// other type if the route has exactly one parameter. Parameters
+    /// captured by nested servers are included.
+    ///
+    /// Responds with `400 Bad Request` naming the offending parameter if
+    /// deserialization fails.
+    Path
+}
+
+extractor! {
+    /// A clone of the application state.
+    State
+}
+
+extractor! {
+    /// A typed request header.
+    ///
+    /// Responds with `400 Bad Request` if the header is missing or fails to
+    /// parse. Use `Option<Header<H>>` for optional headers.
+    Header
+}
+
+extractor! {
+    /// A clone of a request extension, typically set by middleware.
+    ///
+    /// Responds with `500 Internal Server Error` if the extension is missing,
+    /// since that indicates a misconfigured middleware stack rather than a bad
+    /// request.
+    Ext
+}
+
+/// Give I/O errors reading the request body a `400` status, keeping the `413`
+/// of bodies exceeding their limit.
+fn read_error(mut err: http::Error) -> http::Error {
+    let io = err.downcast_ref::<std::io::Error>().is_some();
+    if io && err.status() != StatusCode::PayloadTooLarge {
+        err.set_status(StatusCode::BadRequest);
+    }
+    err
+}
+
+fn is_json(mime: &http::Mime) -> bool {
+    mime.essence() == "application/json"
+        || (mime.basetype() == "application" && mime.subtype().ends_with("+json"))
+}
+
+#[async_trait]
+impl<State, T> FromRequest<State> for Json<T>
+where
+    State: Send + Sync + 'static,
+    T: DeserializeOwned,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        if !req.content_type().iter().any(is_json) {
+            return Err(http::Error::from_str(
+                StatusCode::UnsupportedMediaType,
+                "Expected request with `Content-Type: application/json`",
+            ));
+        }
+
+        let body = req.body_bytes().await.map_err(read_error)?;
+        serde_json::from_slice(&body).map(Json).map_err(|err| {
+            let status = match err.classify() {
+                serde_json::error::Category::Data => StatusCode::UnprocessableEntity,
+                _ => StatusCode::BadRequest,
+            };
+            http::Error::new(status, err)
+        })
+    }
+}
+
+#[async_trait]
+impl<State, T> FromRequest<State> for Form<T>
+where
+    State: Send + Sync + 'static,
+    T: DeserializeOwned,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        let is_form = req
+            .content_type()
+            .iter()
+            .any(|mime| mime.essence() == http::mime::FORM.essence());
+        if !is_form {
+            return Err(http::Error::from_str(
+                StatusCode::UnsupportedMediaType,
+                "Expected request with `Content-Type: application/x-www-form-urlencoded`",
+            ));
+        }
+
+        req.body_form().await.map(Form).map_err(read_error)
+    }
+}
+
+#[async_trait]
+impl<State, T> FromRequest<State> for Query<T>
+where
+    State: Send + Sync + 'static,
+    T: DeserializeOwned,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        req.query().map(Query)
+    }
+}
+
+#[async_trait]
+impl<State, T> FromRequest<State> for Path<T>
+where
+    State: Send + Sync + 'static,
+    T: DeserializeOwned,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        req.params().map(Path)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequest<S> for State<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn from_request(req: &mut Request<S>) -> crate::Result<Self> {
+        Ok(State(req.state().clone()))
+    }
+}
+
+#[async_trait]
+impl<State, T> FromRequest<State> for Ext<T>
+where
+    State: Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        req.ext::<T>().cloned().map(Ext).ok_or_else(|| {
+            http::Error::from_str(
+                StatusCode::InternalServerError,
+                format!("Missing request extension `{}`", std::any::type_name::<T>()),
+            )
+        })
+    }
+}
+
+/// A header with a typed representation, used by the [`Header`] extractor.
+///
+/// This is implemented for the typed headers in [`http_types`].
+pub trait TypedHeader: Sized {
+    /// The name of the header.
+    fn header_name() -> HeaderName;
+
+    /// Parse the header from a set of headers, returning `None` if it is not
+    /// present.
+    fn from_headers(headers: &Headers) -> crate::Result<Option<Self>>;
+}
+
+macro_rules! typed_headers {
+    ($($ty:ty => $name:expr,)*) => {
+        $(
+            impl TypedHeader for $ty {
+                fn header_name() -> HeaderName {
+                    $name
+                }
+
+                fn from_headers(headers: &Headers) -> crate::Result<Option<Self>> {
+                    <$ty>::from_headers(headers)
+                }
+            }
+        )*
+    };
+}
+
+typed_headers! {
+    http::auth::Authorization => headers::AUTHORIZATION,
+    http::auth::BasicAuth => headers::AUTHORIZATION,
+    http::cache::CacheControl => headers::CACHE_CONTROL,
+    http::conditional::IfMatch => headers::IF_MATCH,
+    http::conditional::IfModifiedSince => headers::IF_MODIFIED_SINCE,
+    http::conditional::IfNoneMatch => headers::IF_NONE_MATCH,
+    http::conditional::IfUnmodifiedSince => headers::IF_UNMODIFIED_SINCE,
+    http::content::Accept => headers::ACCEPT,
+    http::content::AcceptEncoding => headers::ACCEPT_ENCODING,
+    http::content::ContentEncoding => headers::CONTENT_ENCODING,
+    http::content::ContentLength => headers::CONTENT_LENGTH,
+    http::content::ContentType => headers::CONTENT_TYPE,
+    http::other::Date => headers::DATE,
+    http::other::Expect => headers::EXPECT,
+}
+
+#[async_trait]
+impl<State, H> FromRequest<State> for Header<H>
+where
+    State: Send + Sync + 'static,
+    H: TypedHeader,
+{
+    async fn from_request(req: &mut Request<State>) -> crate::Result<Self> {
+        let headers: &Headers = req.as_ref();
+        match H::from_headers(headers) {
+            Ok(Some(header)) => Ok(Header(header)),
+            Ok(None) => Err(http::Error::from_str(
+                StatusCode::BadRequest,
+                format!("Missing request header `{}`", H::header_name()),
+            )),
+            Err(err) => Err(http::Error::from_str(
+                StatusCode::BadRequest,
+                format!("Invalid request header `{}`: {}", H::header_name(), err),
+            )),
+        }
+    }
+}
+
+/// Turn a function taking extractors into an [`Endpoint`].
+///
+/// The function may take up to eight arguments implementing
+/// [`FromRequest`], and must return a future resolving to a
+/// `tide::Result<impl Into<Response>>`.
+///
+/// See the [module documentation](self) for an example.
+pub fn handler<F, Args>(f: F) -> Handler<F, Args> {
+    Handler {
+        f,
+        __args: PhantomData,
+    }
+}
+
+/// An [`Endpoint`] that extracts its arguments from the request.
+///
+/// This `struct` is created by [`handler`].
+pub struct Handler<F, Args> {
+    f: F,
+    __args: PhantomData<fn() -> Args>,
+}
+
+impl<F: Clone, Args> Clone for Handler<F, Args> {
+    fn clone(&self) -> Self {
+        handler(self.f.clone())
+    }
+}
+
+impl<F, Args> Debug for Handler<F, Args> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handler").finish()
+    }
+}
+
+macro_rules! impl_handler {
+    ($($arg:ident),*) => {
+        #[async_trait]
+        impl<State, F, Fut, Res, $($arg),*> Endpoint<State> for Handler<F, ($($arg,)*)>
+        where
+            State: Clone + Send + Sync + 'static,
+            F: Fn($($arg),*) -> Fut + Send + Sync + 'static,
+            Fut: Future<Output = crate::Result<Res>> + Send + 'static,
+            Res: Into<Response> + 'static,
+            $($arg: FromRequest<State> + Send + 'static,)*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            async fn call(&self, mut req: Request<State>) -> crate::Result {
+                $(let $arg = $arg::from_request(&mut req).await?;)*
+                let res = (self.f)($($arg),*).await?;
+                Ok(res.into())
+            }
+        }
+    };
+}
+
+impl_handler!();
+impl_handler!(A1);
+impl_handler!(A1, A2);
+impl_handler!(A1, A2, A3);
+impl_handler!(A1, A2, A3, A4);
+impl_handler!(A1, A2, A3, A4, A5);
+impl_handler!(A1, A2, A3, A4, A5, A6);
+impl_handler!(A1, A2, A3, A4, A5, A6, A7);
+impl_handler!(A1, A2, A3, A4, A5, A6, A7, A8);
@@ -0,0 +1,145 @@
+use async_std::fs::{self, File};
+use async_std::io::{prelude::*, BufReader, SeekFrom};
+use async_std::path::PathBuf as AsyncPathBuf;
+use async_std::prelude::*;
+use async_trait::async_trait;
+
+use std::io;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+use super::{FileReader, FileSystem, Metadata};
+
+/// A directory on disk.
+///
+/// Symlinks are followed as long as their target is inside the root
+/// directory; see [`DiskFs::symlinks`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), std::io::Error> {
+/// use tide::fs::{DiskFs, ServeDir};
+///
+/// let mut app = tide::new();
+/// app.at("/static/*")
+///     .serve_dir_with(ServeDir::from_fs(DiskFs::new("public")?));
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiskFs {
+    root: PathBuf,
+    symlinks: Symlinks,
+}
+
+/// How a [`DiskFs`] treats symlinks.
+///
+/// Paths that are refused because of a symlink are reported as not found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symlinks {
+    /// Follow symlinks whose target is inside the root directory.
+    #[default]
+    WithinRoot,
+    /// Follow all symlinks, wherever they point to.
+    Follow,
+    /// Refuse paths that go through a symlink.
+    Deny,
+}
+
+impl DiskFs {
+    /// Create a new instance of `DiskFs` rooted at the directory `root`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `root` does not exist.
+    pub fn new(root: impl AsRef<Path>) -> io::Result<Self> {
+        // Verify path exists, return error if it doesn't.
+        let root = root.as_ref().canonicalize()?;
+        Ok(Self {
+            root,
+            symlinks: Symlinks::default(),
+        })
+    }
+
+    /// Set how symlinks are treated. Defaults to [`Symlinks::WithinRoot`].
+    ///
+    /// Symlinks are checked after resolving them, so a symlink to a
+    /// directory outside the root cannot be used to reach files inside it
+    /// either.
+    #[must_use]
+    pub fn symlinks(mut self, symlinks: Symlinks) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Resolve `path` against the root, refusing anything that is not a
+    /// plain relative path, such as a drive prefix on Windows, and anything
+    /// the symlink policy does not allow.
+    async fn resolve(&self, path: &str) -> io::Result<AsyncPathBuf> {
+        let path = Path::new(path);
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(io::ErrorKind::PermissionDenied.into());
+        }
+        let path = AsyncPathBuf::from(self.root.join(path));
+        if self.symlinks == Symlinks::Follow {
+            return Ok(path);
+        }
+
+        // The root is canonical and `path` has no `.` or `..` segments, so
+        // it only differs from its canonical form if it goes through a
+        // symlink.
+        let canonical = path.canonicalize().await?;
+        let allowed = match self.symlinks {
+            Symlinks::Deny => canonical == path,
+            Symlinks::WithinRoot | Symlinks::Follow => canonical.starts_with(&self.root),
+        };
+        if allowed {
+            Ok(canonical)
+        } else {
+            Err(io::ErrorKind::NotFound.into())
+        }
+    }
+}
+
+#[async_trait]
+impl FileSystem for DiskFs {
+    async fn metadata(&self, path: &str) -> io::Result<Metadata> {
+        let metadata = fs::metadata(self.resolve(path).await?).await?;
+        let modified = metadata.modified().ok();
+        Ok(if metadata.is_dir() {
+            Metadata::dir(modified)
+        } else {
+            Metadata::file(metadata.len(), modified)
+        })
+    }
+
+    async fn read(&self, path: &str, range: Range<u64>) -> io::Result<FileReader> {
+        let mut file = File::open(self.resolve(path).await?).await?;
+        if range.start > 0 {
+            file.seek(SeekFrom::Start(range.start)).await?;
+        }
+        Ok(Box::new(BufReader::new(file.take(range.end - range.start))))
+    }
+
+    async fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        let mut names = Vec::new();
+        let mut entries = fs::read_dir(self.resolve(path).await?).await?;
+        while let Some(entry) = entries.next().await {
+            names.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        Ok(names)
+    }
+
+    async fn canonicalize(&self, path: &str) -> Option<String> {
+        let canonical = self.resolve(path).await.ok()?.canonicalize().await.ok()?;
+        let relative = canonical.strip_prefix(&self.root).ok()?;
+        let segments: Option<Vec<_>> = relative
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect();
+        Some(segments?.join("/"))
+    }
+}
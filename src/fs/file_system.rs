@@ -0,0 +1,122 @@
+use async_std::io::BufRead;
+use async_trait::async_trait;
+
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// A reader returned by [`FileSystem::read`].
+pub type FileReader = Box<dyn BufRead + Unpin + Send + Sync>;
+
+/// A read-only file system that static files are served from.
+///
+/// Paths passed to its methods are relative to the root of the file system,
+/// use `/` as their separator, and never contain empty, `.` or `..`
+/// segments. The root itself is the empty path.
+///
+/// Implemented by [`DiskFs`](super::DiskFs) and
+/// [`MemoryFs`](super::MemoryFs). Serving any file system gets the same
+/// MIME detection, conditional requests and range requests.
+#[async_trait]
+pub trait FileSystem: Send + Sync + 'static {
+    /// Get the metadata of the file or directory at `path`.
+    ///
+    /// Returns an error of kind [`io::ErrorKind::NotFound`] if it does not
+    /// exist.
+    async fn metadata(&self, path: &str) -> io::Result<Metadata>;
+
+    /// Read `range` of the file at `path`.
+    ///
+    /// `range` is never past the end of the file.
+    async fn read(&self, path: &str, range: Range<u64>) -> io::Result<FileReader>;
+
+    /// List the names of the entries of the directory at `path`.
+    async fn read_dir(&self, path: &str) -> io::Result<Vec<String>>;
+
+    /// The path of the file or directory `path` refers to once symlinks are
+    /// resolved, or `None` if it is outside the root or does not exist.
+    ///
+    /// Used to hide the targets of symlinks as well. Defaults to `path`
+    /// itself, for file systems without symlinks.
+    async fn canonicalize(&self, path: &str) -> Option<String> {
+        Some(path.to_owned())
+    }
+}
+
+/// The metadata of a file or directory in a [`FileSystem`].
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    is_dir: bool,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Metadata {
+    /// The metadata of a file of `len` bytes.
+    #[must_use]
+    pub fn file(len: u64, modified: Option<SystemTime>) -> Self {
+        Self {
+            is_dir: false,
+            len,
+            modified,
+        }
+    }
+
+    /// The metadata of a directory.
+    #[must_use]
+    pub fn dir(modified: Option<SystemTime>) -> Self {
+        Self {
+            is_dir: true,
+            len: 0,
+            modified,
+        }
+    }
+
+    /// Whether this is the metadata of a directory.
+    #[must_use]
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    /// The size of the file in bytes, or `0` for directories.
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// The time of the last modification, if known.
+    ///
+    /// Files without one are served without `ETag` and `Last-Modified`
+    /// headers.
+    #[must_use]
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+}
+
+/// A shared [`FileSystem`], so endpoints serving it can be cloned and
+/// debugged.
+#[derive(Clone)]
+pub(crate) struct SharedFs(Arc<dyn FileSystem>);
+
+impl SharedFs {
+    pub(crate) fn new(fs: impl FileSystem) -> Self {
+        Self(Arc::new(fs))
+    }
+}
+
+impl std::ops::Deref for SharedFs {
+    type Target = dyn FileSystem;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl Debug for SharedFs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("FileSystem")
+    }
+}
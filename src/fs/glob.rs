@@ -0,0 +1,94 @@
+use regex::Regex;
+
+/// A glob pattern matched against the relative path of a file.
+///
+/// `*` matches any characters but `/`, `**` matches any characters including
+/// `/`, `?` matches a single character but `/`, and `{a,b}` matches either
+/// alternative. A pattern without a `/` is matched against the file name
+/// only, so `*.html` matches HTML files in any directory, while a leading `/`
+/// anchors a pattern at the root.
+#[derive(Debug, Clone)]
+pub(crate) struct Glob {
+    regex: Regex,
+    match_name: bool,
+}
+
+impl Glob {
+    pub(crate) fn new(pattern: &str) -> Self {
+        let match_name = !pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+        let mut regex = String::from("^");
+        let mut in_group = false;
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                '{' if !in_group => {
+                    in_group = true;
+                    regex.push_str("(?:");
+                }
+                '}' if in_group => {
+                    in_group = false;
+                    regex.push(')');
+                }
+                ',' if in_group => regex.push('|'),
+                c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        if in_group {
+            regex.push(')');
+        }
+        regex.push('$');
+
+        Self {
+            regex: Regex::new(&regex).expect("a glob is a valid regex"),
+            match_name,
+        }
+    }
+
+    /// Whether the relative `path` of a file matches this pattern.
+    pub(crate) fn matches(&self, path: &str) -> bool {
+        let path = if self.match_name {
+            path.rsplit('/').next().unwrap_or(path)
+        } else {
+            path
+        };
+        self.regex.is_match(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Glob;
+
+    #[test]
+    fn matches() {
+        let matches = |pattern, path| Glob::new(pattern).matches(path);
+        assert!(matches("*.html", "index.html"));
+        assert!(matches("*.html", "docs/guide/index.html"));
+        assert!(!matches("*.html", "index.htm"));
+        assert!(matches("*.{js,css}", "assets/app.css"));
+        assert!(!matches("*.{js,css}", "assets/app.json"));
+        assert!(matches("assets/*", "assets/app.js"));
+        assert!(!matches("assets/*", "assets/img/logo.png"));
+        assert!(matches("/assets/**", "assets/img/logo.png"));
+        assert!(matches("**/*.min.js", "app.min.js"));
+        assert!(matches("**/*.min.js", "lib/vendor/app.min.js"));
+        assert!(matches("app.????????.js", "app.1a2b3c4d.js"));
+        assert!(!matches("app.????????.js", "app.js"));
+        assert!(matches("a+b(1).txt", "a+b(1).txt"));
+        assert!(matches("drafts", "blog/drafts"));
+        assert!(matches("/drafts", "drafts"));
+        assert!(!matches("/drafts", "blog/drafts"));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::http::conditional::LastModified;
+use crate::http::{mime, Body};
+use crate::{Response, StatusCode};
+
+use serde_json::json;
+
+use std::fmt::Write;
+use std::io;
+use std::time::SystemTime;
+
+use super::FileSystem;
+
+/// An entry of a directory listing.
+#[derive(Debug)]
+pub(crate) struct Entry {
+    name: String,
+    is_dir: bool,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Entry {
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn modified(&self) -> Option<String> {
+        let modified = LastModified::new(self.modified?);
+        Some(modified.value().as_str().to_owned())
+    }
+}
+
+/// Read the entries of the directory at `path`, directories first, sorted by
+/// name.
+pub(crate) async fn read_dir(fs: &dyn FileSystem, path: &str) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for name in fs.read_dir(path).await? {
+        let metadata = match fs.metadata(&super::join(path, &name)).await {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        entries.push(Entry {
+            name,
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+            modified: metadata.modified(),
+        });
+    }
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// Render a listing of the directory at `url_path` as JSON or HTML.
+pub(crate) fn render(url_path: &str, is_root: bool, entries: &[Entry], json: bool) -> Response {
+    let body = if json {
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "name": entry.name,
+                    "type": if entry.is_dir { "directory" } else { "file" },
+                    "size": if entry.is_dir { None } else { Some(entry.size) },
+                    "modified": entry.modified(),
+                })
+            })
+            .collect();
+        Body::from_json(&entries).expect("a listing is valid JSON")
+    } else {
+        let mut body = Body::from_string(html(url_path, is_root, entries));
+        body.set_mime(mime::HTML);
+        body
+    };
+    Response::builder(StatusCode::Ok).body(body).build()
+}
+
+fn html(url_path: &str, is_root: bool, entries: &[Entry]) -> String {
+    let title = format!("Index of {}", escape(url_path));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n\
+         <body>\n<h1>{0}</h1>\n<table>\n\
+         <tr><th>Name</th><th>Size</th><th>Last modified</th></tr>\n",
+        title
+    );
+    if !is_root {
+        html.push_str("<tr><td><a href=\"../\">../</a></td><td></td><td></td></tr>\n");
+    }
+    for entry in entries {
+        let (suffix, size) = if entry.is_dir {
+            ("/", String::new())
+        } else {
+            ("", entry.size.to_string())
+        };
+        writeln!(
+            html,
+            "<tr><td><a href=\"{}{suffix}\">{}{suffix}</a></td><td>{}</td><td>{}</td></tr>",
+            encode(&entry.name),
+            escape(&entry.name),
+            size,
+            entry.modified().unwrap_or_default(),
+            suffix = suffix,
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Escape `s` for use in HTML text and attributes.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode a file name for use as a relative URL.
+fn encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            byte => write!(encoded, "%{:02X}", byte).unwrap(),
+        }
+    }
+    encoded
+}
@@ -0,0 +1,277 @@
+use async_std::io::Cursor;
+use async_trait::async_trait;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::iter::FromIterator;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use super::{FileReader, FileSystem, Metadata};
+
+type Contents = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// A file system held in memory.
+///
+/// Useful to embed assets in a binary with `include_bytes!`, or to serve the
+/// contents of an archive. All files share a modification time, which
+/// defaults to the time the `MemoryFs` was created.
+///
+/// # Examples
+///
+/// ```
+/// use tide::fs::{MemoryFs, ServeDir};
+///
+/// let fs = MemoryFs::new()
+///     .file("index.html", "<h1>Hello</h1>")
+///     .file("img/logo.svg", &b"<svg></svg>"[..]);
+///
+/// let mut app = tide::new();
+/// app.at("/*").serve_dir_with(ServeDir::from_fs(fs));
+/// ```
+///
+/// Files can also be collected from an iterator of paths and contents:
+///
+/// ```
+/// use tide::fs::MemoryFs;
+///
+/// let fs: MemoryFs = vec![("a.txt", "a"), ("b.txt", "b")].into_iter().collect();
+/// ```
+#[derive(Clone)]
+pub struct MemoryFs {
+    files: BTreeMap<String, Contents>,
+    dirs: BTreeSet<String>,
+    modified: SystemTime,
+}
+
+impl MemoryFs {
+    /// Create a new, empty instance of `MemoryFs`.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut dirs = BTreeSet::new();
+        dirs.insert(String::new());
+        Self {
+            files: BTreeMap::new(),
+            dirs,
+            modified: SystemTime::now(),
+        }
+    }
+
+    /// Add a file at `path`, creating its parent directories.
+    ///
+    /// Leading slashes and `.` segments of `path` are ignored.
+    #[must_use]
+    pub fn file(mut self, path: &str, contents: impl AsRef<[u8]> + Send + Sync + 'static) -> Self {
+        self.insert(path, Arc::new(contents));
+        self
+    }
+
+    /// Set the modification time of all files.
+    #[must_use]
+    pub fn modified(mut self, modified: SystemTime) -> Self {
+        self.modified = modified;
+        self
+    }
+
+    fn insert(&mut self, path: &str, contents: Contents) {
+        let path = normalize(path);
+        let mut parent = path.as_str();
+        while let Some((dir, _)) = parent.rsplit_once('/') {
+            self.dirs.insert(dir.to_owned());
+            parent = dir;
+        }
+        self.files.insert(path, contents);
+    }
+
+    /// Read all files of a tar archive into memory.
+    ///
+    /// Compressed archives can be read by wrapping `reader` in a decoder, such
+    /// as `flate2::read::GzDecoder`. The modification time of the `MemoryFs`
+    /// is the latest one in the archive.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the archive cannot be read.
+    #[cfg(feature = "fs-tar")]
+    pub fn from_tar(reader: impl io::Read) -> io::Result<Self> {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let mut fs = Self::new().modified(UNIX_EPOCH);
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            if let Ok(mtime) = entry.header().mtime() {
+                fs.modified = fs.modified.max(UNIX_EPOCH + Duration::from_secs(mtime));
+            }
+            let kind = entry.header().entry_type();
+            if kind.is_dir() {
+                fs.dirs.insert(normalize(&path));
+            } else if kind.is_file() {
+                let mut contents = Vec::with_capacity(entry.size() as usize);
+                io::Read::read_to_end(&mut entry, &mut contents)?;
+                fs.insert(&path, Arc::new(contents));
+            }
+        }
+        Ok(fs)
+    }
+
+    /// Read all files of a zip archive into memory.
+    ///
+    /// Stored and deflated entries are supported. The modification time of
+    /// the `MemoryFs` is the latest one in the archive.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the archive cannot be read.
+    #[cfg(feature = "fs-zip")]
+    pub fn from_zip(reader: impl io::Read + io::Seek) -> io::Result<Self> {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let mut fs = Self::new().modified(UNIX_EPOCH);
+        let mut archive = zip::ZipArchive::new(reader)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        for index in 0..archive.len() {
+            let mut file = archive
+                .by_index(index)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let path = match file.enclosed_name() {
+                Some(path) => path.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            if let Some(time) = file.last_modified() {
+                let days = days_from_civil(time.year().into(), time.month(), time.day());
+                let secs = u64::from(time.hour()) * 3600
+                    + u64::from(time.minute()) * 60
+                    + u64::from(time.second());
+                let mtime = UNIX_EPOCH + Duration::from_secs(days * 86400 + secs);
+                fs.modified = fs.modified.max(mtime);
+            }
+            if file.is_dir() {
+                fs.dirs.insert(normalize(&path));
+            } else {
+                let mut contents = Vec::with_capacity(file.size() as usize);
+                io::Read::read_to_end(&mut file, &mut contents)?;
+                fs.insert(&path, Arc::new(contents));
+            }
+        }
+        Ok(fs)
+    }
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for MemoryFs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryFs")
+            .field("files", &self.files.keys().collect::<Vec<_>>())
+            .field("modified", &self.modified)
+            .finish()
+    }
+}
+
+impl<P, C> FromIterator<(P, C)> for MemoryFs
+where
+    P: AsRef<str>,
+    C: AsRef<[u8]> + Send + Sync + 'static,
+{
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(iter: I) -> Self {
+        let mut fs = Self::new();
+        for (path, contents) in iter {
+            fs.insert(path.as_ref(), Arc::new(contents));
+        }
+        fs
+    }
+}
+
+#[async_trait]
+impl FileSystem for MemoryFs {
+    async fn metadata(&self, path: &str) -> io::Result<Metadata> {
+        if let Some(contents) = self.files.get(path) {
+            let len = (**contents).as_ref().len() as u64;
+            Ok(Metadata::file(len, Some(self.modified)))
+        } else if self.dirs.contains(path) {
+            Ok(Metadata::dir(Some(self.modified)))
+        } else {
+            Err(io::ErrorKind::NotFound.into())
+        }
+    }
+
+    async fn read(&self, path: &str, range: Range<u64>) -> io::Result<FileReader> {
+        let contents = self
+            .files
+            .get(path)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        let mut cursor = Cursor::new(Slice {
+            contents: contents.clone(),
+            end: range.end as usize,
+        });
+        cursor.set_position(range.start);
+        Ok(Box::new(cursor))
+    }
+
+    async fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        if !self.dirs.contains(path) {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+        let prefix = if path.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", path)
+        };
+        let children = |entry: &String| {
+            let name = entry.strip_prefix(&prefix)?;
+            match name {
+                "" => None,
+                name if name.contains('/') => None,
+                name => Some(name.to_owned()),
+            }
+        };
+        Ok(self
+            .dirs
+            .iter()
+            .filter_map(children)
+            .chain(self.files.keys().filter_map(children))
+            .collect())
+    }
+}
+
+/// The contents of a file up to `end`.
+struct Slice {
+    contents: Contents,
+    end: usize,
+}
+
+impl AsRef<[u8]> for Slice {
+    fn as_ref(&self) -> &[u8] {
+        &(*self.contents).as_ref()[..self.end]
+    }
+}
+
+/// Remove leading slashes and empty or `.` segments from `path`.
+fn normalize(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The number of days between 1970-01-01 and a date.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+#[cfg(feature = "fs-zip")]
+fn days_from_civil(year: i64, month: u8, day: u8) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146_097 + day_of_era - 719_468).max(0) as u64
+}
@@ -1,5 +1,222 @@
+//! Static file serving.
+//!
+//! Files are usually served with [`Route::serve_dir`](crate::Route::serve_dir)
+//! and [`Route::serve_file`](crate::Route::serve_file). A [`ServeDir`] or
+//! [`ServeFile`] can be configured and mounted with
+//! [`Route::serve_dir_with`](crate::Route::serve_dir_with) and
+//! [`Route::serve_file_with`](crate::Route::serve_file_with).
+//!
+//! Files are read from the disk by default, or from any other [`FileSystem`]
+//! with [`ServeDir::from_fs`] and [`ServeFile::from_fs`]. [`MemoryFs`] serves
+//! files held in memory, such as assets embedded in the binary or the
+//! contents of a tar or zip archive, read with the `fs-tar` and `fs-zip`
+//! features respectively.
+
+mod disk;
+mod file_system;
+mod glob;
+mod listing;
+mod memory;
+mod range;
 mod serve_dir;
 mod serve_file;
 
-pub(crate) use serve_dir::ServeDir;
-pub(crate) use serve_file::ServeFile;
+pub use disk::{DiskFs, Symlinks};
+pub use file_system::{FileReader, FileSystem, Metadata};
+pub use memory::MemoryFs;
+pub use serve_dir::ServeDir;
+pub use serve_file::ServeFile;
+
+pub(crate) use file_system::SharedFs;
+
+use crate::accept_encoding;
+use crate::conditional::Conditions;
+use crate::http::conditional::{ETag, LastModified};
+use crate::http::headers::{
+    ACCEPT_ENCODING, ACCEPT_RANGES, CONTENT_ENCODING, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED,
+    VARY,
+};
+use crate::http::{mime, Mime};
+use crate::{Body, Request, Response, Result, StatusCode};
+
+use async_std::io::prelude::*;
+use kv_log_macro::warn;
+use range::Ranges;
+
+use std::borrow::Cow;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Precompressed variants looked for next to a file, as pairs of content
+/// coding and file extension, in order of preference.
+const PRECOMPRESSED: [(&str, &str); 3] = [("br", "br"), ("zstd", "zst"), ("gzip", "gz")];
+
+/// Respond with the file at `path` in `fs`, answering conditional and range
+/// requests with the file's size and modification time as validators.
+///
+/// If `precompressed` is set, a precompressed variant of the file is served
+/// instead if one exists and is accepted by the client.
+async fn serve<State>(
+    req: &Request<State>,
+    fs: &dyn FileSystem,
+    path: &str,
+    precompressed: bool,
+) -> Result {
+    let metadata = match fs.metadata(path).await {
+        Ok(metadata) if !metadata.is_dir() => metadata,
+        Ok(_) => {
+            warn!("Not a file: {:?}", path);
+            return Ok(Response::new(StatusCode::NotFound));
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            warn!("File not found: {:?}", path);
+            return Ok(Response::new(StatusCode::NotFound));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let (file, file_len) = (path, metadata.len());
+
+    let mut res = Response::new(StatusCode::Ok);
+    res.insert_header(ACCEPT_RANGES, "bytes");
+    let mut path = Cow::Borrowed(path);
+    let mut metadata = metadata;
+    let mut tag_suffix = String::new();
+    if precompressed {
+        let variants = precompressed_variants(fs, &path).await?;
+        if !variants.is_empty() {
+            res.append_header(VARY, "Accept-Encoding");
+        }
+        let codings: Vec<_> = variants.iter().map(|(coding, ..)| *coding).collect();
+        let accept_encoding = req
+            .header(ACCEPT_ENCODING)
+            .map_or("", |value| value.as_str());
+        if let Some(coding) = accept_encoding::negotiate(accept_encoding, &codings) {
+            let (_, variant, variant_metadata) =
+                variants.into_iter().find(|(c, ..)| *c == coding).unwrap();
+            res.insert_header(CONTENT_ENCODING, coding);
+            path = Cow::Owned(variant);
+            metadata = variant_metadata;
+            tag_suffix = format!("-{}", coding);
+        }
+    }
+    let len = metadata.len();
+    // The modification time only has a resolution of seconds, so a file may
+    // change without its validators changing. A modification time is only
+    // used as a strong validator once it is at least a second old.
+    let mut strong_date = false;
+    if let Some(modified) = metadata.modified() {
+        let secs = modified
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        ETag::new_weak(format!("{:x}-{:x}{}", len, secs, tag_suffix)).apply(&mut res);
+        LastModified::new(modified).apply(&mut res);
+        strong_date = SystemTime::now()
+            .duration_since(modified)
+            .is_ok_and(|age| age >= Duration::from_secs(1));
+    }
+
+    // Files are only read once the validators show they are needed.
+    let mut res = Conditions::new(req).respond(res);
+    if res.status() != StatusCode::Ok {
+        return Ok(res);
+    }
+    let mime = detect_mime(fs, file, file_len).await?;
+    let mut body = Body::from_reader(fs.read(&path, 0..len).await?, Some(len as usize));
+    body.set_mime(mime.clone());
+    res.set_body(body);
+    if !if_range_matches(req, &res, strong_date) {
+        return Ok(res);
+    }
+    let ranges = match req.header("Range") {
+        Some(range) => Ranges::parse(range.as_str(), len),
+        None => None,
+    };
+    match ranges {
+        None => {}
+        Some(Ranges::Unsatisfiable) => {
+            res = Response::builder(StatusCode::RequestedRangeNotSatisfiable)
+                .header(ACCEPT_RANGES, "bytes")
+                .header(CONTENT_RANGE, format!("bytes */{}", len))
+                .build();
+        }
+        Some(Ranges::Satisfiable(ranges)) => {
+            res.set_status(StatusCode::PartialContent);
+            if let [range] = ranges.as_slice() {
+                res.insert_header(CONTENT_RANGE, range::content_range(range, len));
+                res.set_body(range::single(fs, &path, range, mime).await?);
+            } else {
+                let body = range::multipart(fs, &path, &ranges, len, mime).await?;
+                res.set_content_type(body.mime().clone());
+                res.set_body(body);
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Detect the MIME type of the file at `path` from its first bytes, falling
+/// back to its extension and then to `application/octet-stream`.
+async fn detect_mime(fs: &dyn FileSystem, path: &str, len: u64) -> io::Result<Mime> {
+    // We need to read the first 300 bytes to correctly infer formats such as tar.
+    let mut head = Vec::with_capacity(300);
+    fs.read(path, 0..len.min(300))
+        .await?
+        .read_to_end(&mut head)
+        .await?;
+    let mut buf = [0_u8; 300];
+    buf[..head.len()].copy_from_slice(&head);
+    let mime = Mime::sniff(&buf).ok().or_else(|| {
+        let (_, extension) = path.rsplit_once('.')?;
+        Mime::from_extension(extension)
+    });
+    Ok(mime.unwrap_or(mime::BYTE_STREAM))
+}
+
+/// The existing precompressed variants of the file at `path`, with their
+/// content codings and metadata.
+async fn precompressed_variants(
+    fs: &dyn FileSystem,
+    path: &str,
+) -> io::Result<Vec<(&'static str, String, Metadata)>> {
+    let mut variants = Vec::new();
+    for (coding, extension) in PRECOMPRESSED.iter() {
+        let variant = format!("{}.{}", path, extension);
+        match fs.metadata(&variant).await {
+            Ok(metadata) if !metadata.is_dir() => variants.push((*coding, variant, metadata)),
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(variants)
+}
+
+/// Join a file name to the relative path of a directory.
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// Whether the request's `If-Range` header, if any, matches the validators
+/// of `res`. Only strong `ETag`s match, and dates only match exactly and if
+/// `Last-Modified` is `strong`.
+fn if_range_matches<State>(req: &Request<State>, res: &Response, strong: bool) -> bool {
+    let if_range = match req.header(IF_RANGE) {
+        Some(if_range) => if_range.as_str().trim(),
+        None => return true,
+    };
+    let validator = if if_range.starts_with('"') {
+        ETAG
+    } else if if_range.starts_with("W/") || !strong {
+        return false;
+    } else {
+        LAST_MODIFIED
+    };
+    res.header(validator)
+        .into_iter()
+        .any(|value| value.as_str() == if_range)
+}
@@ -0,0 +1,180 @@
+use crate::http::{Body, Mime};
+
+use async_std::io::{self, prelude::*, BufReader, Cursor};
+
+use super::FileSystem;
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::Range;
+
+/// The maximum number of ranges served in a single response. Requests for
+/// more are answered with the full file instead.
+const MAX_RANGES: usize = 16;
+
+type Reader = Box<dyn io::Read + Unpin + Send + Sync>;
+
+/// The byte ranges requested by a `Range` header.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Ranges {
+    /// At least one range overlaps the file.
+    Satisfiable(Vec<Range<u64>>),
+    /// No range overlaps the file.
+    Unsatisfiable,
+}
+
+impl Ranges {
+    /// Parse a `Range` header for a file of `len` bytes.
+    ///
+    /// Returns `None` if the header is malformed, uses a unit other than
+    /// `bytes`, or asks for too many ranges, in which case it should be
+    /// ignored.
+    pub(crate) fn parse(header: &str, len: u64) -> Option<Self> {
+        let (unit, specs) = header.split_once('=')?;
+        if !unit.trim().eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+
+        let mut ranges = Vec::new();
+        for spec in specs.split(',') {
+            let (start, end) = spec.trim().split_once('-')?;
+            let range = if start.is_empty() {
+                let suffix: u64 = end.parse().ok()?;
+                len.saturating_sub(suffix)..len
+            } else {
+                let start: u64 = start.parse().ok()?;
+                let end = match end {
+                    "" => len,
+                    end => {
+                        let end: u64 = end.parse().ok()?;
+                        if end < start {
+                            return None;
+                        }
+                        end.saturating_add(1).min(len)
+                    }
+                };
+                start..end
+            };
+            if range.start < range.end {
+                ranges.push(range);
+            }
+        }
+
+        if ranges.len() > MAX_RANGES {
+            None
+        } else if ranges.is_empty() {
+            Some(Ranges::Unsatisfiable)
+        } else {
+            Some(Ranges::Satisfiable(coalesce(ranges)))
+        }
+    }
+}
+
+/// Merge overlapping and adjacent `ranges`, so that no byte of the file is
+/// sent more than once, and sort them by their start.
+fn coalesce(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// The value of a `Content-Range` header for `range` of a file of `len`
+/// bytes.
+pub(crate) fn content_range(range: &Range<u64>, len: u64) -> String {
+    format!("bytes {}-{}/{}", range.start, range.end - 1, len)
+}
+
+/// A body with a single `range` of the file at `path`.
+pub(crate) async fn single(
+    fs: &dyn FileSystem,
+    path: &str,
+    range: &Range<u64>,
+    mime: Mime,
+) -> io::Result<Body> {
+    let len = (range.end - range.start) as usize;
+    let mut body = Body::from_reader(fs.read(path, range.clone()).await?, Some(len));
+    body.set_mime(mime);
+    Ok(body)
+}
+
+/// A `multipart/byteranges` body with several `ranges` of the file at `path`,
+/// which has `len` bytes and type `mime`.
+pub(crate) async fn multipart(
+    fs: &dyn FileSystem,
+    path: &str,
+    ranges: &[Range<u64>],
+    len: u64,
+    mime: Mime,
+) -> io::Result<Body> {
+    let boundary = boundary();
+    let mut reader: Reader = Box::new(io::empty());
+    let mut body_len = 0;
+    for range in ranges {
+        let headers = format!(
+            "--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+            boundary,
+            mime,
+            content_range(range, len)
+        );
+        body_len += headers.len() + (range.end - range.start) as usize + 2;
+        reader = Box::new(
+            reader
+                .chain(Cursor::new(headers))
+                .chain(fs.read(path, range.clone()).await?)
+                .chain(Cursor::new("\r\n")),
+        );
+    }
+    let end = format!("--{}--\r\n", boundary);
+    body_len += end.len();
+    reader = Box::new(reader.chain(Cursor::new(end)));
+
+    let mut body = Body::from_reader(BufReader::new(reader), Some(body_len));
+    let mime = format!("multipart/byteranges; boundary={}", boundary);
+    body.set_mime(mime.parse::<Mime>().unwrap());
+    Ok(body)
+}
+
+/// A random multipart boundary.
+fn boundary() -> String {
+    let random = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", random(), random())
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ranges;
+
+    #[test]
+    fn parse() {
+        let parse = |header: &str| Ranges::parse(header, 100);
+        let ok = |ranges: &[(u64, u64)]| {
+            let ranges = ranges.iter().map(|&(start, end)| start..end).collect();
+            Some(Ranges::Satisfiable(ranges))
+        };
+        assert_eq!(parse("bytes=0-9"), ok(&[(0, 10)]));
+        assert_eq!(parse("bytes=90-"), ok(&[(90, 100)]));
+        assert_eq!(parse("bytes=-10"), ok(&[(90, 100)]));
+        assert_eq!(parse("bytes=-200"), ok(&[(0, 100)]));
+        assert_eq!(parse("bytes=50-500"), ok(&[(50, 100)]));
+        assert_eq!(parse("bytes=0-0, 200-300, -1"), ok(&[(0, 1), (99, 100)]));
+        assert_eq!(parse("bytes=100-"), Some(Ranges::Unsatisfiable));
+        assert_eq!(parse("bytes=-0"), Some(Ranges::Unsatisfiable));
+        assert_eq!(parse("bytes=9-0"), None);
+        assert_eq!(parse("bytes=a-b"), None);
+        assert_eq!(parse("items=0-9"), None);
+        assert_eq!(parse(&format!("bytes={}", vec!["0-0"; 17].join(","))), None);
+
+        // Overlapping and adjacent ranges are merged.
+        assert_eq!(parse("bytes=0-,0-,0-"), ok(&[(0, 100)]));
+        assert_eq!(
+            parse("bytes=50-59, 0-9, 10-19, 55-70"),
+            ok(&[(0, 20), (50, 71)])
+        );
+    }
+}
@@ -1,20 +1,416 @@
-use crate::{Body, Endpoint, Request, Response, Result, StatusCode};
+use crate::http::cache::{CacheControl, CacheDirective, Expires};
+use crate::http::headers::ACCEPT;
+use crate::{Endpoint, Redirect, Request, Response, Result, StatusCode};
 
-use async_std::path::PathBuf as AsyncPathBuf;
 use kv_log_macro::{info, warn};
 
-use std::path::{Path, PathBuf};
-use std::{ffi::OsStr, io};
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Component, Path};
 
-pub(crate) struct ServeDir {
+use super::glob::Glob;
+use super::{join, listing, DiskFs, FileSystem, SharedFs};
+
+/// The extensions of paths treated as asset requests by default.
+const DEFAULT_ASSET_EXTENSIONS: &[&str] = &[
+    "js", "mjs", "css", "map", "json", "wasm", "png", "jpg", "jpeg", "gif", "svg", "ico", "webp",
+    "avif", "woff", "woff2", "ttf", "otf", "eot", "mp3", "mp4", "webm", "txt", "xml", "pdf",
+];
+
+/// Serve a directory of static files.
+///
+/// This is the endpoint behind [`Route::serve_dir`](crate::Route::serve_dir).
+/// Mount a configured instance with
+/// [`Route::serve_dir_with`](crate::Route::serve_dir_with).
+///
+/// Requests for a directory without a trailing slash are redirected to the
+/// same path with one, so relative links in its index resolve correctly.
+/// Requests for a directory with a trailing slash are answered with its first
+/// existing [index file](ServeDir::index_files), or with a generated listing
+/// if [`autoindex`](ServeDir::autoindex) is enabled.
+///
+/// Single-page applications can set a [`fallback`](ServeDir::fallback) file
+/// that is served for paths that do not exist.
+///
+/// Files are read from a directory on disk, or from any other
+/// [`FileSystem`] with [`ServeDir::from_fs`].
+///
+/// Files and directories whose name starts with a dot are hidden unless
+/// [`hidden_files`](ServeDir::hidden_files) is enabled, and more paths can be
+/// hidden or exposed with [`deny`](ServeDir::deny) and
+/// [`allow`](ServeDir::allow). Hidden paths are answered with
+/// `404 Not Found`, as if they did not exist, and left out of listings.
+/// Symlinks are followed as long as they point inside the directory; see
+/// [`DiskFs::symlinks`] to change that. Symlinks to hidden paths are hidden
+/// as well.
+///
+/// Responses carry `ETag` and `Last-Modified` headers from the file's
+/// metadata. [`cache_control`](ServeDir::cache_control) rules add
+/// `Cache-Control` headers, and `Expires` headers with
+/// [`expires`](ServeDir::expires).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use async_std::task::block_on;
+/// # fn main() -> Result<(), std::io::Error> { block_on(async {
+/// #
+/// use tide::fs::ServeDir;
+///
+/// let mut app = tide::new();
+/// app.at("/docs/*")
+///     .serve_dir_with(ServeDir::new("public/docs")?.index_files(["index.htm"]).autoindex(true));
+/// app.listen("127.0.0.1:8080").await?;
+/// #
+/// # Ok(()) }) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServeDir {
     prefix: String,
-    dir: PathBuf,
+    fs: SharedFs,
+    index_files: Vec<String>,
+    autoindex: bool,
+    precompressed: bool,
+    fallback: Option<String>,
+    asset_extensions: Vec<String>,
+    cache_rules: Vec<(Glob, Vec<CacheDirective>)>,
+    expires: bool,
+    hidden_files: bool,
+    allow: Vec<Glob>,
+    deny: Vec<Glob>,
 }
 
 impl ServeDir {
-    /// Create a new instance of `ServeDir`.
-    pub(crate) fn new(prefix: String, dir: PathBuf) -> Self {
-        Self { prefix, dir }
+    /// Create a new instance of `ServeDir` for the directory `dir`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `dir` does not exist.
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::from_fs(DiskFs::new(dir)?))
+    }
+
+    /// Create a new instance of `ServeDir` for the root of `fs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tide::fs::{MemoryFs, ServeDir};
+    ///
+    /// let fs = MemoryFs::new().file("index.html", include_str!("../../README.md"));
+    ///
+    /// let mut app = tide::new();
+    /// app.at("/*").serve_dir_with(ServeDir::from_fs(fs));
+    /// ```
+    #[must_use]
+    pub fn from_fs(fs: impl FileSystem) -> Self {
+        Self {
+            prefix: String::new(),
+            fs: SharedFs::new(fs),
+            index_files: vec!["index.html".to_owned()],
+            autoindex: false,
+            precompressed: false,
+            fallback: None,
+            asset_extensions: DEFAULT_ASSET_EXTENSIONS
+                .iter()
+                .map(|extension| (*extension).to_owned())
+                .collect(),
+            cache_rules: Vec::new(),
+            expires: false,
+            hidden_files: false,
+            allow: Vec::new(),
+            deny: Vec::new(),
+        }
+    }
+
+    /// Set the files to look for, in order, when a directory is requested.
+    ///
+    /// Defaults to `index.html`. Pass an empty list to disable index files.
+    #[must_use]
+    pub fn index_files<I>(mut self, files: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.index_files = files.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// List the contents of directories without an index file.
+    ///
+    /// The listing is rendered as HTML, or as JSON if the client prefers
+    /// `application/json`. It includes the names, sizes and modification
+    /// times of the directory's entries. Defaults to `false`.
+    #[must_use]
+    pub fn autoindex(mut self, autoindex: bool) -> Self {
+        self.autoindex = autoindex;
+        self
+    }
+
+    /// Serve precompressed variants of files when the client accepts them.
+    ///
+    /// For a request for `app.js`, the files `app.js.br`, `app.js.zst` and
+    /// `app.js.gz` are served if they exist and the client's
+    /// `Accept-Encoding` allows it, with the `Content-Type` of `app.js` and a
+    /// matching `Content-Encoding`. The response gets a
+    /// `Vary: Accept-Encoding` header whenever any variant exists. Defaults
+    /// to `false`.
+    #[must_use]
+    pub fn precompressed(mut self, precompressed: bool) -> Self {
+        self.precompressed = precompressed;
+        self
+    }
+
+    /// Serve `file` instead of `404 Not Found` for paths that do not exist,
+    /// as needed by single-page applications with client-side routing.
+    ///
+    /// `file` is relative to the served directory, and usually `index.html`.
+    /// Paths that look like requests for assets, based on their
+    /// [extension](ServeDir::asset_extensions), still get a `404 Not Found`.
+    #[must_use]
+    pub fn fallback(mut self, file: impl AsRef<Path>) -> Self {
+        let segments = file
+            .as_ref()
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(segment) => Some(segment.to_string_lossy()),
+                _ => None,
+            });
+        self.fallback = Some(segments.collect::<Vec<_>>().join("/"));
+        self
+    }
+
+    /// Set the file extensions of paths that are treated as asset requests,
+    /// and so never answered with the [fallback](ServeDir::fallback) file.
+    ///
+    /// Extensions are given without a leading dot and matched
+    /// case-insensitively. Defaults to common script, style, image, font and
+    /// data extensions such as `js`, `css`, `png` and `woff2`.
+    #[must_use]
+    pub fn asset_extensions<I>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.asset_extensions = extensions
+            .into_iter()
+            .map(|extension| extension.into().to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Whether `path` looks like a request for an asset.
+    fn is_asset(&self, path: &str) -> bool {
+        Path::new(path)
+            .extension()
+            .and_then(OsStr::to_str)
+            .into_iter()
+            .any(|extension| {
+                self.asset_extensions
+                    .iter()
+                    .any(|asset| asset.eq_ignore_ascii_case(extension))
+            })
+    }
+
+    /// Add a `Cache-Control` header with `directives` to files matching the
+    /// glob `pattern`.
+    ///
+    /// In patterns, `*` matches any characters but `/`, `**` matches any
+    /// characters including `/`, `?` matches a single character but `/`, and
+    /// `{a,b}` matches either alternative. A pattern without a `/` is matched
+    /// against the file name only, so `*.html` matches HTML files in any
+    /// directory, while a leading `/` anchors a pattern at the root of the
+    /// served directory.
+    ///
+    /// The first rule that matches a file is used. Files matching no rule get
+    /// no `Cache-Control` header.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), std::io::Error> {
+    /// use std::time::Duration;
+    /// use tide::fs::ServeDir;
+    /// use tide::http::cache::CacheDirective;
+    ///
+    /// let year = Duration::from_secs(365 * 24 * 60 * 60);
+    /// let serve_dir = ServeDir::new("dist")?
+    ///     .cache_control("assets/**", vec![
+    ///         CacheDirective::Public,
+    ///         CacheDirective::MaxAge(year),
+    ///         CacheDirective::Immutable,
+    ///     ])
+    ///     .cache_control("*.html", vec![CacheDirective::NoCache])
+    ///     .expires(true);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn cache_control<I>(mut self, pattern: &str, directives: I) -> Self
+    where
+        I: IntoIterator<Item = CacheDirective>,
+    {
+        let directives = directives.into_iter().collect();
+        self.cache_rules.push((Glob::new(pattern), directives));
+        self
+    }
+
+    /// Add an `Expires` header to files with a `max-age` directive from their
+    /// [`cache_control`](ServeDir::cache_control) rule, for caches that only
+    /// understand HTTP/1.0. Defaults to `false`.
+    #[must_use]
+    pub fn expires(mut self, expires: bool) -> Self {
+        self.expires = expires;
+        self
+    }
+
+    /// Serve files and directories whose name starts with a dot, such as
+    /// `.env` or `.git/`. Defaults to `false`.
+    #[must_use]
+    pub fn hidden_files(mut self, hidden_files: bool) -> Self {
+        self.hidden_files = hidden_files;
+        self
+    }
+
+    /// Hide paths matching the glob `pattern`, and everything inside
+    /// directories matching it.
+    ///
+    /// Patterns are written like for
+    /// [`cache_control`](ServeDir::cache_control), so `*.bak` hides backup
+    /// files in any directory and `/drafts` hides the `drafts` directory at
+    /// the root.
+    #[must_use]
+    pub fn deny(mut self, pattern: &str) -> Self {
+        self.deny.push(Glob::new(pattern));
+        self
+    }
+
+    /// Serve paths matching the glob `pattern`, and everything inside
+    /// directories matching it, even if they are hidden files or match a
+    /// [`deny`](ServeDir::deny) pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), std::io::Error> {
+    /// use tide::fs::ServeDir;
+    ///
+    /// let serve_dir = ServeDir::new("public")?
+    ///     .allow("/.well-known")
+    ///     .deny("*.map");
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn allow(mut self, pattern: &str) -> Self {
+        self.allow.push(Glob::new(pattern));
+        self
+    }
+
+    /// Whether the file or directory at `path` is hidden from clients.
+    fn is_hidden(&self, path: &str) -> bool {
+        // `path` and the paths of the directories it is in.
+        let paths = path
+            .match_indices('/')
+            .map(|(index, _)| &path[..index])
+            .chain(Some(path).filter(|path| !path.is_empty()));
+        let matches = |globs: &[Glob]| {
+            paths
+                .clone()
+                .any(|path| globs.iter().any(|glob| glob.matches(path)))
+        };
+
+        if matches(&self.allow) {
+            false
+        } else if !self.hidden_files && path.split('/').any(|name| name.starts_with('.')) {
+            true
+        } else {
+            matches(&self.deny)
+        }
+    }
+
+    /// Whether the file or directory at `path`, or the target of a symlink
+    /// at `path`, is hidden from clients.
+    async fn is_hidden_target(&self, path: &str) -> bool {
+        if self.is_hidden(path) {
+            return true;
+        }
+        match self.fs.canonicalize(path).await {
+            Some(target) if target != path => self.is_hidden(&target),
+            _ => false,
+        }
+    }
+
+    /// Set the route path this instance is mounted at.
+    pub(crate) fn set_prefix(&mut self, prefix: String) {
+        self.prefix = prefix;
+    }
+
+    /// Serve the file at `path` with the caching headers of the first
+    /// matching rule.
+    async fn serve_file<State>(&self, req: &Request<State>, path: &str) -> Result {
+        let mut res = super::serve(req, &*self.fs, path, self.precompressed).await?;
+        if !res.status().is_success() && res.status() != StatusCode::NotModified {
+            return Ok(res);
+        }
+        if let Some((_, directives)) = self.cache_rules.iter().find(|(glob, _)| glob.matches(path))
+        {
+            let mut cache_control = CacheControl::new();
+            for directive in directives {
+                match directive {
+                    CacheDirective::MaxAge(max_age) if self.expires => {
+                        Expires::new(*max_age).apply(&mut res);
+                    }
+                    _ => {}
+                }
+                cache_control.push(directive.clone());
+            }
+            cache_control.apply(&mut res);
+        }
+        Ok(res)
+    }
+
+    async fn serve_directory<State>(&self, req: &Request<State>, path: &str) -> Result {
+        let url = req.url();
+        if !url.path().ends_with('/') {
+            // Redirect relative to the current path, as an absolute path
+            // starting with `//` would be taken as another host.
+            let segment = url.path().rsplit('/').next().unwrap_or_default();
+            let mut location = if segment.contains(':') {
+                format!("./{}/", segment)
+            } else {
+                format!("{}/", segment)
+            };
+            if let Some(query) = url.query() {
+                location.push('?');
+                location.push_str(query);
+            }
+            return Ok(Redirect::permanent(location).into());
+        }
+
+        for index in &self.index_files {
+            let file = join(path, index);
+            if self.is_hidden_target(&file).await {
+                continue;
+            }
+            if let Ok(metadata) = self.fs.metadata(&file).await {
+                if !metadata.is_dir() {
+                    return self.serve_file(req, &file).await;
+                }
+            }
+        }
+
+        if !self.autoindex {
+            warn!("Directory without index requested: {:?}", path);
+            return Ok(Response::new(StatusCode::NotFound));
+        }
+
+        let mut entries = Vec::new();
+        for entry in listing::read_dir(&*self.fs, path).await? {
+            if !self.is_hidden_target(&join(path, entry.name())).await {
+                entries.push(entry);
+            }
+        }
+        let json = prefers_json(req);
+        Ok(listing::render(url.path(), path.is_empty(), &entries, json))
     }
 }
 
@@ -24,39 +420,93 @@ where
     State: Clone + Send + Sync + 'static,
 {
     async fn call(&self, req: Request<State>) -> Result {
-        let path = req.url().path();
-        let path = path
+        let url_path = req.url().path();
+        // The mount root without a trailing slash, such as `/docs` for
+        // `/docs/*`, is served as the root, which redirects to `docs/`.
+        let url_path = url_path
             .strip_prefix(self.prefix.trim_end_matches('*'))
-            .unwrap();
-        let path = path.trim_start_matches('/');
-        let mut file_path = self.dir.clone();
-        for p in Path::new(path) {
-            if p == OsStr::new(".") {
-                continue;
-            } else if p == OsStr::new("..") {
-                file_path.pop();
-            } else {
-                file_path.push(p);
+            .unwrap_or_default();
+
+        info!("Requested file: {:?}", url_path);
+
+        let path = match normalize(url_path) {
+            Some(path) => path,
+            None => {
+                warn!("Unauthorized attempt to read: {:?}", url_path);
+                return Ok(Response::new(StatusCode::Forbidden));
+            }
+        };
+        let res = if self.is_hidden_target(&path).await {
+            warn!("Hidden path requested: {:?}", path);
+            Response::new(StatusCode::NotFound)
+        } else {
+            match self.fs.metadata(&path).await {
+                Ok(metadata) if metadata.is_dir() => self.serve_directory(&req, &path).await?,
+                _ => self.serve_file(&req, &path).await?,
+            }
+        };
+
+        match &self.fallback {
+            Some(fallback) if res.status() == StatusCode::NotFound && !self.is_asset(&path) => {
+                self.serve_file(&req, fallback).await
             }
+            _ => Ok(res),
         }
+    }
+}
 
-        info!("Requested file: {:?}", file_path);
+/// Turn the percent-encoded path of a request into a path relative to the
+/// served directory.
+///
+/// Returns `None` if the path escapes the directory, or has a segment that
+/// decodes to a separator or a NUL byte.
+fn normalize(url_path: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    for segment in url_path.split('/') {
+        let segment = percent_decode(segment)?;
+        match segment.as_str() {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment if segment.contains(&['/', '\\', '\0'][..]) => return None,
+            _ => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
 
-        let file_path = AsyncPathBuf::from(file_path);
-        if !file_path.starts_with(&self.dir) {
-            warn!("Unauthorized attempt to read: {:?}", file_path);
-            Ok(Response::new(StatusCode::Forbidden))
-        } else {
-            match Body::from_file(&file_path).await {
-                Ok(body) => Ok(Response::builder(StatusCode::Ok).body(body).build()),
-                Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                    warn!("File not found: {:?}", &file_path);
-                    Ok(Response::new(StatusCode::NotFound))
-                }
-                Err(e) => Err(e.into()),
+/// Decode a percent-encoded path segment, or return `None` if it is not
+/// valid UTF-8.
+fn percent_decode(segment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut rest = segment.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match (byte, tail) {
+            (b'%', [high, low, ..]) if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                let hex = |digit: u8| (digit as char).to_digit(16).unwrap() as u8;
+                bytes.push(hex(*high) << 4 | hex(*low));
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
             }
         }
     }
+    String::from_utf8(bytes).ok()
+}
+
+/// Whether the request's `Accept` header asks for JSON before HTML.
+fn prefers_json<State>(req: &Request<State>) -> bool {
+    req.header(ACCEPT)
+        .into_iter()
+        .flat_map(|values| values.iter())
+        .flat_map(|value| value.as_str().split(','))
+        .map(|media_type| media_type.split(';').next().unwrap_or_default().trim())
+        .find(|essence| *essence == "text/html" || *essence == "application/json")
+        .into_iter()
+        .any(|essence| essence == "application/json")
 }
 
 #[cfg(test)]
@@ -74,10 +524,9 @@ mod test {
         let mut file = File::create(file_path)?;
         write!(file, "Foobar")?;
 
-        Ok(ServeDir {
-            prefix: "/static/".to_string(),
-            dir: static_dir,
-        })
+        let mut serve_dir = ServeDir::new(static_dir)?;
+        serve_dir.set_prefix("/static/".to_string());
+        Ok(serve_dir)
     }
 
     fn request(path: &str) -> crate::Request<()> {
@@ -1,36 +1,84 @@
-use crate::{Body, Endpoint, Request, Response, Result, StatusCode};
+use crate::{Endpoint, Request, Result};
 use std::io;
 use std::path::Path;
 
-use async_std::path::PathBuf as AsyncPathBuf;
 use async_trait::async_trait;
-use kv_log_macro::warn;
 
-pub(crate) struct ServeFile {
-    path: AsyncPathBuf,
+use super::{DiskFs, FileSystem, SharedFs};
+
+/// Serve a single static file.
+///
+/// This is the endpoint behind
+/// [`Route::serve_file`](crate::Route::serve_file). Mount a configured
+/// instance with [`Route::serve_file_with`](crate::Route::serve_file_with).
+///
+/// The file is read from the disk, or from any other [`FileSystem`] with
+/// [`ServeFile::from_fs`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use async_std::task::block_on;
+/// # fn main() -> Result<(), std::io::Error> { block_on(async {
+/// #
+/// use tide::fs::ServeFile;
+///
+/// let mut app = tide::new();
+/// app.at("/app.js")
+///     .serve_file_with(ServeFile::new("dist/app.js")?.precompressed(true));
+/// app.listen("127.0.0.1:8080").await?;
+/// #
+/// # Ok(()) }) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServeFile {
+    fs: SharedFs,
+    path: String,
+    precompressed: bool,
 }
 
 impl ServeFile {
-    /// Create a new instance of `ServeFile`.
-    pub(crate) fn init(path: impl AsRef<Path>) -> io::Result<Self> {
-        let file = path.as_ref().to_owned().canonicalize()?;
-        Ok(Self {
-            path: AsyncPathBuf::from(file),
-        })
+    /// Create a new instance of `ServeFile` for the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `path` does not exist.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = path.as_ref().canonicalize()?;
+        let (dir, name) = match (file.parent(), file.file_name()) {
+            (Some(dir), Some(name)) => (dir, name.to_string_lossy().into_owned()),
+            _ => return Err(io::ErrorKind::InvalidInput.into()),
+        };
+        Ok(Self::from_fs(DiskFs::new(dir)?, name))
+    }
+
+    /// Create a new instance of `ServeFile` for the file at `path` in `fs`.
+    ///
+    /// `path` is relative to the root of `fs`, with `/` as its separator.
+    #[must_use]
+    pub fn from_fs(fs: impl FileSystem, path: impl Into<String>) -> Self {
+        Self {
+            fs: SharedFs::new(fs),
+            path: path.into(),
+            precompressed: false,
+        }
+    }
+
+    /// Serve precompressed variants of the file when the client accepts them.
+    ///
+    /// See [`ServeDir::precompressed`](super::ServeDir::precompressed).
+    /// Defaults to `false`.
+    #[must_use]
+    pub fn precompressed(mut self, precompressed: bool) -> Self {
+        self.precompressed = precompressed;
+        self
     }
 }
 
 #[async_trait]
 impl<State: Clone + Send + Sync + 'static> Endpoint<State> for ServeFile {
-    async fn call(&self, _: Request<State>) -> Result {
-        match Body::from_file(&self.path).await {
-            Ok(body) => Ok(Response::builder(StatusCode::Ok).body(body).build()),
-            Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                warn!("File not found: {:?}", &self.path);
-                Ok(Response::new(StatusCode::NotFound))
-            }
-            Err(e) => Err(e.into()),
-        }
+    async fn call(&self, req: Request<State>) -> Result {
+        super::serve(&req, &*self.fs, &self.path, self.precompressed).await
     }
 }
 
@@ -50,7 +98,7 @@ mod test {
         let mut file = File::create(&file_path)?;
         write!(file, "Foobar")?;
 
-        Ok(ServeFile::init(file_path)?)
+        Ok(ServeFile::new(file_path)?)
     }
 
     fn request(path: &str) -> crate::Request<()> {
@@ -72,9 +120,7 @@ mod test {
 
     #[async_std::test]
     async fn should_serve_404_when_file_missing() {
-        let serve_file = ServeFile {
-            path: AsyncPathBuf::from("gone/file"),
-        };
+        let serve_file = ServeFile::from_fs(crate::fs::MemoryFs::new(), "gone/file");
 
         let res: Response = serve_file.call(request("static/foo")).await.unwrap().into();
 
@@ -63,11 +63,12 @@
 #![doc(html_favicon_url = "https://yoshuawuyts.com/assets/http-rs/favicon.ico")]
 #![doc(html_logo_url = "https://yoshuawuyts.com/assets/http-rs/logo-rounded.png")]
 
+mod accept_encoding;
 #[cfg(feature = "cookies")]
 mod cookies;
 mod endpoint;
-mod fs;
 mod middleware;
+mod params;
 mod redirect;
 mod request;
 mod response;
@@ -76,17 +77,29 @@ mod route;
 mod router;
 mod server;
 
+pub mod conditional;
 pub mod convert;
+pub mod extract;
+pub mod fs;
 pub mod listener;
 pub mod log;
 pub mod prelude;
 pub mod security;
+pub mod testing;
 pub mod utils;
 
+#[cfg(feature = "compress")]
+pub mod compress;
+#[cfg(feature = "multipart")]
+pub mod multipart;
+#[cfg(feature = "proxy")]
+pub mod proxy;
 #[cfg(feature = "sessions")]
 pub mod sessions;
 #[cfg(feature = "sse")]
 pub mod sse;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 pub use endpoint::Endpoint;
 pub use middleware::{Middleware, Next};
@@ -1,4 +1,4 @@
-use crate::listener::{ListenInfo, Listener, ToListener};
+use crate::listener::{ListenInfo, Listener, Shutdown, ToListener};
 use crate::Server;
 
 use std::fmt::{self, Debug, Display, Formatter};
@@ -109,6 +109,12 @@ where
         Ok(())
     }
 
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        for listener in self.listeners.iter_mut() {
+            listener.set_shutdown(shutdown.clone());
+        }
+    }
+
     fn info(&self) -> Vec<ListenInfo> {
         self.listeners
             .iter()
@@ -0,0 +1,81 @@
+#[cfg(feature = "h1-server")]
+use super::ConnectionOptions;
+use super::Shutdown;
+
+/// Information about a connection served with
+/// [`Server::serve_connection`](crate::Server::serve_connection).
+///
+/// # Examples
+///
+/// ```
+/// use tide::listener::{ConnectionInfo, Shutdown};
+///
+/// let shutdown = Shutdown::new();
+/// let info = ConnectionInfo::new()
+///     .peer_addr("vsock:3:1024")
+///     .local_addr("vsock:2:8080")
+///     .shutdown(shutdown.clone());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionInfo {
+    pub(crate) peer_addr: Option<String>,
+    pub(crate) local_addr: Option<String>,
+    pub(crate) tls: bool,
+    pub(crate) shutdown: Option<Shutdown>,
+    #[cfg(feature = "h1-server")]
+    pub(crate) options: ConnectionOptions,
+}
+
+impl ConnectionInfo {
+    /// Create a new instance of `ConnectionInfo` with no addresses.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the address of the remote end of the connection, as returned by
+    /// [`Request::peer_addr`](crate::Request::peer_addr).
+    #[must_use]
+    pub fn peer_addr(mut self, addr: impl ToString) -> Self {
+        self.peer_addr = Some(addr.to_string());
+        self
+    }
+
+    /// Set the address of the local end of the connection, as returned by
+    /// [`Request::local_addr`](crate::Request::local_addr).
+    #[must_use]
+    pub fn local_addr(mut self, addr: impl ToString) -> Self {
+        self.local_addr = Some(addr.to_string());
+        self
+    }
+
+    /// Set whether the connection is encrypted, in which case request URLs
+    /// have the `https` scheme.
+    #[must_use]
+    pub fn tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Track the connection with a [`Shutdown`] signal.
+    ///
+    /// Once the signal is triggered, responses ask the client to close the
+    /// connection and an idle kept-alive connection is closed. The
+    /// connection counts as in-flight until it closes or the drain timeout
+    /// elapses.
+    #[must_use]
+    pub fn shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Set the options the connection is served with.
+    ///
+    /// The `nodelay` and `accept_backoff` options only apply to listeners.
+    #[cfg(feature = "h1-server")]
+    #[must_use]
+    pub fn options(mut self, options: ConnectionOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
@@ -0,0 +1,330 @@
+use async_std::io::{self, Read, Write};
+use async_std::task;
+use futures_util::future::BoxFuture;
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use super::Shutdown;
+
+/// Options for the HTTP/1 connections served by a listener.
+///
+/// Set them on a [`TcpListener`](super::TcpListener) or
+/// [`UnixListener`](super::UnixListener) with `with_options`, on a
+/// `TlsListener` with `TlsListenerBuilder::options`, or on a single
+/// connection with [`ConnectionInfo::options`](super::ConnectionInfo::options).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use async_std::task::block_on;
+/// # fn main() -> Result<(), std::io::Error> { block_on(async {
+/// #
+/// use std::net::SocketAddr;
+/// use std::time::Duration;
+/// use tide::listener::{ConnectionOptions, TcpListener};
+///
+/// let options = ConnectionOptions::new()
+///     .keep_alive_timeout(Duration::from_secs(5))
+///     .header_read_timeout(Duration::from_secs(10))
+///     .max_requests(1000)
+///     .nodelay(true);
+///
+/// let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+/// let mut app = tide::new();
+/// app.at("/").get(|_| async { Ok("Hello, world!") });
+/// app.listen(TcpListener::from_addrs(vec![addr]).with_options(options))
+///     .await?;
+/// #
+/// # Ok(()) }) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionOptions {
+    pub(crate) keep_alive: bool,
+    pub(crate) keep_alive_timeout: Option<Duration>,
+    pub(crate) header_read_timeout: Option<Duration>,
+    pub(crate) max_header_size: Option<usize>,
+    pub(crate) max_requests: Option<usize>,
+    pub(crate) nodelay: bool,
+    pub(crate) accept_backoff: Duration,
+}
+
+impl ConnectionOptions {
+    /// Create a new instance of `ConnectionOptions` with the default options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether connections are kept open for further requests once a
+    /// response has been sent. Defaults to `true`.
+    #[must_use]
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Set how long a kept-alive connection may be idle before the next
+    /// request starts, after which it is closed.
+    ///
+    /// Defaults to no timeout other than the 60 second timeout async-h1
+    /// applies to reading request heads, which is also the longest timeout
+    /// that takes effect.
+    #[must_use]
+    pub fn keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.keep_alive_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long a client may take to send the head of a request, counted
+    /// from the start of the connection for the first request and from the
+    /// first byte for later ones. Connections that are too slow are closed,
+    /// which protects against slowloris attacks.
+    ///
+    /// Defaults to no timeout other than the 60 second timeout async-h1
+    /// applies to reading request heads, which is also the longest timeout
+    /// that takes effect.
+    #[must_use]
+    pub fn header_read_timeout(mut self, timeout: Duration) -> Self {
+        self.header_read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the largest request head, in bytes, that is accepted. Connections
+    /// sending larger heads are closed.
+    ///
+    /// Defaults to and cannot exceed the 8 KiB limit of async-h1.
+    #[must_use]
+    pub fn max_header_size(mut self, size: usize) -> Self {
+        self.max_header_size = Some(size);
+        self
+    }
+
+    /// Set the number of requests served on a connection before it is
+    /// closed. Defaults to no limit.
+    #[must_use]
+    pub fn max_requests(mut self, requests: usize) -> Self {
+        self.max_requests = Some(requests);
+        self
+    }
+
+    /// Set the `TCP_NODELAY` option on TCP connections, which disables
+    /// Nagle's algorithm. Defaults to `false`.
+    #[must_use]
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.nodelay = nodelay;
+        self
+    }
+
+    /// Set how long to pause accepting connections after an error such as
+    /// running out of file descriptors. Defaults to 500 milliseconds.
+    #[must_use]
+    pub fn accept_backoff(mut self, backoff: Duration) -> Self {
+        self.accept_backoff = backoff;
+        self
+    }
+
+    /// Whether a response to the `requests`th request on a connection should
+    /// close it.
+    pub(crate) fn closes_after(&self, requests: usize) -> bool {
+        !self.keep_alive || self.max_requests.is_some_and(|max| requests >= max)
+    }
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self {
+            keep_alive: true,
+            keep_alive_timeout: None,
+            header_read_timeout: None,
+            max_header_size: None,
+            max_requests: None,
+            nodelay: false,
+            accept_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// A stream that enforces the timeouts and size limit of
+/// [`ConnectionOptions`] while request heads are read from it.
+///
+/// Clones share their state, as async-h1 reads from clones of the stream.
+#[derive(Clone)]
+pub(crate) struct Limited<RW> {
+    inner: RW,
+    state: Arc<Mutex<HeadState>>,
+}
+
+struct HeadState {
+    keep_alive_timeout: Option<Duration>,
+    header_read_timeout: Option<Duration>,
+    max_header_size: Option<usize>,
+    /// Whether a request head is being read.
+    reading: bool,
+    /// Whether the head being read is the first on the connection.
+    first: bool,
+    /// Whether the first byte of the head has been read.
+    started: bool,
+    /// Bytes of the head read so far.
+    read: usize,
+    /// The last bytes read, to find the end of the head across reads.
+    tail: [u8; 3],
+    deadline: Option<BoxFuture<'static, ()>>,
+    /// Resolves once shutdown is triggered, to close idle connections.
+    shutdown: Option<BoxFuture<'static, ()>>,
+}
+
+impl<RW> Limited<RW> {
+    pub(crate) fn new(inner: RW, options: &ConnectionOptions, shutdown: Option<&Shutdown>) -> Self {
+        let shutdown = shutdown.cloned().map(|shutdown| -> BoxFuture<'static, ()> {
+            Box::pin(async move { shutdown.wait().await })
+        });
+        let state = HeadState {
+            keep_alive_timeout: options.keep_alive_timeout,
+            header_read_timeout: options.header_read_timeout,
+            max_header_size: options.max_header_size,
+            reading: false,
+            first: false,
+            started: false,
+            read: 0,
+            tail: [0; 3],
+            deadline: None,
+            shutdown,
+        };
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Start reading the head of a request, the first on the connection if
+    /// `first` is set.
+    pub(crate) fn start_head(&self, first: bool) {
+        let mut state = self.state.lock().unwrap();
+        let timeout = if first {
+            state.header_read_timeout
+        } else {
+            state.keep_alive_timeout
+        };
+        state.reading = true;
+        state.first = first;
+        state.started = false;
+        state.read = 0;
+        state.tail = [0; 3];
+        state.deadline = timeout.map(sleep);
+    }
+}
+
+impl HeadState {
+    /// Account for `bytes` read from the stream.
+    fn consume(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if !self.reading || bytes.is_empty() {
+            return Ok(());
+        }
+        if !self.started {
+            self.started = true;
+            // The header timeout of the first request already runs from the
+            // start of the connection.
+            if !self.first {
+                self.deadline = self.header_read_timeout.map(sleep);
+            }
+        }
+
+        let mut len = bytes.len();
+        let mut window = self.tail;
+        for (index, byte) in bytes.iter().enumerate() {
+            if window == *b"\r\n\r" && *byte == b'\n' {
+                self.reading = false;
+                self.deadline = None;
+                len = index + 1;
+                break;
+            }
+            window = [window[1], window[2], *byte];
+        }
+        self.tail = window;
+        self.read += len;
+
+        match self.max_header_size {
+            Some(max) if self.read > max => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Request head is too large",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn sleep(timeout: Duration) -> BoxFuture<'static, ()> {
+    Box::pin(task::sleep(timeout))
+}
+
+/// Whether `error` was caused by a [`Limited`] stream closing the connection,
+/// on a timeout or once shutdown is triggered.
+pub(crate) fn is_closed(error: &crate::http::Error) -> bool {
+    error.downcast_ref::<io::Error>().is_some_and(|error| {
+        matches!(
+            error.kind(),
+            io::ErrorKind::TimedOut | io::ErrorKind::ConnectionAborted
+        )
+    })
+}
+
+impl<RW: Read + Unpin> Read for Limited<RW> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let state = self.state.clone();
+        let mut state = state.lock().unwrap();
+        if let Some(deadline) = &mut state.deadline {
+            if deadline.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "Timed out reading the request head",
+                )));
+            }
+        }
+
+        let len = match Pin::new(&mut self.inner).poll_read(cx, buf) {
+            Poll::Ready(Ok(len)) => len,
+            Poll::Pending => {
+                // A connection waiting for its next request is idle, and is
+                // closed once shutdown is triggered rather than kept open
+                // until a timeout.
+                let idle = state.reading && !state.started;
+                if let Some(shutdown) = state.shutdown.as_mut().filter(|_| idle) {
+                    if shutdown.as_mut().poll(cx).is_ready() {
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::ConnectionAborted,
+                            "Closed an idle connection on shutdown",
+                        )));
+                    }
+                }
+                return Poll::Pending;
+            }
+            other => return other,
+        };
+        Poll::Ready(state.consume(&buf[..len]).map(|()| len))
+    }
+}
+
+impl<RW: Write + Unpin> Write for Limited<RW> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::listener::{Listener, ToListener};
+use crate::listener::{Listener, Shutdown, ToListener};
 use crate::Server;
 
 use std::fmt::{self, Debug, Display, Formatter};
@@ -137,6 +137,12 @@ where
         }
     }
 
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        for listener in self.listeners.iter_mut().flatten() {
+            listener.set_shutdown(shutdown.clone());
+        }
+    }
+
     fn info(&self) -> Vec<ListenInfo> {
         match self.index {
             Some(index) => match self.listeners.get(index) {
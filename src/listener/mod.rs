@@ -1,11 +1,17 @@
 //! Types that represent HTTP transports and binding
 
 mod concurrent_listener;
+mod connection_info;
+#[cfg(feature = "h1-server")]
+mod connection_options;
 mod failover_listener;
 #[cfg(feature = "h1-server")]
 mod parsed_listener;
+mod shutdown;
 #[cfg(feature = "h1-server")]
 mod tcp_listener;
+#[cfg(feature = "rustls")]
+mod tls_listener;
 mod to_listener;
 #[cfg(feature = "h1-server")]
 mod to_listener_impls;
@@ -13,6 +19,8 @@ mod to_listener_impls;
 mod unix_listener;
 
 use std::fmt::{Debug, Display};
+use std::net::SocketAddr;
+use std::path::PathBuf;
 
 use async_std::io;
 use async_trait::async_trait;
@@ -20,15 +28,28 @@ use async_trait::async_trait;
 use crate::Server;
 
 pub use concurrent_listener::ConcurrentListener;
+pub use connection_info::ConnectionInfo;
+#[cfg(feature = "h1-server")]
+pub use connection_options::ConnectionOptions;
 pub use failover_listener::FailoverListener;
+pub use shutdown::Shutdown;
 pub use to_listener::ToListener;
 
+#[cfg(feature = "rustls")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "rustls")))]
+pub use futures_rustls::rustls;
+#[cfg(feature = "rustls")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "rustls")))]
+pub use tls_listener::{TlsListener, TlsListenerBuilder};
+
+#[cfg(feature = "h1-server")]
+pub(crate) use connection_options::{is_closed, Limited};
 #[cfg(feature = "h1-server")]
 pub(crate) use parsed_listener::ParsedListener;
 #[cfg(feature = "h1-server")]
-pub(crate) use tcp_listener::TcpListener;
+pub use tcp_listener::TcpListener;
 #[cfg(all(unix, feature = "h1-server"))]
-pub(crate) use unix_listener::UnixListener;
+pub use unix_listener::UnixListener;
 
 /// The Listener trait represents an implementation of http transport for a tide
 /// application. In order to provide a Listener to tide, you will also need to
@@ -48,9 +69,32 @@ where
     /// after `bind` has succeeded.
     async fn accept(&mut self) -> io::Result<()>;
 
+    /// Register a [`Shutdown`] signal with the listener. Once the signal is
+    /// triggered, `accept` should stop accepting new connections, wait for
+    /// in-flight connections to finish and return `Ok(())`. This method must
+    /// be called before `accept`.
+    ///
+    /// The default implementation ignores the signal.
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        let _ = shutdown;
+    }
+
     /// Expose information about the connection. This should always return valid
     /// data after `bind` has succeeded.
     fn info(&self) -> Vec<ListenInfo>;
+
+    /// The addresses the listener is bound to, once `bind` has succeeded.
+    ///
+    /// Unlike the addresses the listener was created with, these include the
+    /// port assigned by the operating system when binding to port `0`.
+    ///
+    /// The default implementation collects the addresses of [`Listener::info`].
+    fn local_addrs(&self) -> Vec<ListenAddr> {
+        self.info()
+            .into_iter()
+            .filter_map(|info| info.local_addr().cloned())
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -67,9 +111,17 @@ where
         self.as_mut().accept().await
     }
 
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.as_mut().set_shutdown(shutdown)
+    }
+
     fn info(&self) -> Vec<ListenInfo> {
         self.as_ref().info()
     }
+
+    fn local_addrs(&self) -> Vec<ListenAddr> {
+        self.as_ref().local_addrs()
+    }
 }
 
 /// crate-internal shared logic used by tcp and unix listeners to
@@ -93,6 +145,7 @@ pub struct ListenInfo {
     conn_string: String,
     transport: String,
     tls: bool,
+    local_addr: Option<ListenAddr>,
 }
 
 impl ListenInfo {
@@ -105,9 +158,17 @@ impl ListenInfo {
             conn_string,
             transport,
             tls,
+            local_addr: None,
         }
     }
 
+    /// Set the address the listener is bound to.
+    #[must_use]
+    pub fn with_local_addr(mut self, local_addr: impl Into<ListenAddr>) -> Self {
+        self.local_addr = Some(local_addr.into());
+        self
+    }
+
     /// Get the connection string.
     pub fn connection(&self) -> &str {
         self.conn_string.as_str()
@@ -124,6 +185,11 @@ impl ListenInfo {
     pub fn is_encrypted(&self) -> bool {
         self.tls
     }
+
+    /// The address the listener is bound to, if known.
+    pub fn local_addr(&self) -> Option<&ListenAddr> {
+        self.local_addr.as_ref()
+    }
 }
 
 impl Display for ListenInfo {
@@ -131,3 +197,51 @@ impl Display for ListenInfo {
         write!(f, "{}", self.conn_string)
     }
 }
+
+/// The address a [`Listener`] is bound to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddr {
+    /// A TCP socket address.
+    Tcp(SocketAddr),
+    /// The path of a Unix domain socket.
+    Unix(PathBuf),
+}
+
+impl ListenAddr {
+    /// The TCP socket address, if this is one.
+    pub fn tcp(&self) -> Option<SocketAddr> {
+        match self {
+            Self::Tcp(addr) => Some(*addr),
+            Self::Unix(_) => None,
+        }
+    }
+
+    /// The path of the Unix domain socket, if this is one.
+    pub fn unix(&self) -> Option<&std::path::Path> {
+        match self {
+            Self::Unix(path) => Some(path),
+            Self::Tcp(_) => None,
+        }
+    }
+}
+
+impl From<SocketAddr> for ListenAddr {
+    fn from(addr: SocketAddr) -> Self {
+        Self::Tcp(addr)
+    }
+}
+
+impl From<PathBuf> for ListenAddr {
+    fn from(path: PathBuf) -> Self {
+        Self::Unix(path)
+    }
+}
+
+impl Display for ListenAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{}", addr),
+            Self::Unix(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
@@ -1,6 +1,8 @@
+#[cfg(feature = "rustls")]
+use super::TlsListener;
 #[cfg(unix)]
 use super::UnixListener;
-use super::{ListenInfo, Listener, TcpListener};
+use super::{ListenInfo, Listener, Shutdown, TcpListener};
 use crate::Server;
 
 use async_std::io;
@@ -17,6 +19,8 @@ pub enum ParsedListener<State> {
     #[cfg(unix)]
     Unix(UnixListener<State>),
     Tcp(TcpListener<State>),
+    #[cfg(feature = "rustls")]
+    Tls(TlsListener<State>),
 }
 
 impl<State> Debug for ParsedListener<State> {
@@ -25,6 +29,8 @@ impl<State> Debug for ParsedListener<State> {
             #[cfg(unix)]
             ParsedListener::Unix(unix) => Debug::fmt(unix, f),
             ParsedListener::Tcp(tcp) => Debug::fmt(tcp, f),
+            #[cfg(feature = "rustls")]
+            ParsedListener::Tls(tls) => Debug::fmt(tls, f),
         }
     }
 }
@@ -35,6 +41,8 @@ impl<State> Display for ParsedListener<State> {
             #[cfg(unix)]
             Self::Unix(u) => write!(f, "{}", u),
             Self::Tcp(t) => write!(f, "{}", t),
+            #[cfg(feature = "rustls")]
+            Self::Tls(t) => write!(f, "{}", t),
         }
     }
 }
@@ -49,6 +57,8 @@ where
             #[cfg(unix)]
             Self::Unix(u) => u.bind(server).await,
             Self::Tcp(t) => t.bind(server).await,
+            #[cfg(feature = "rustls")]
+            Self::Tls(t) => t.bind(server).await,
         }
     }
 
@@ -57,6 +67,18 @@ where
             #[cfg(unix)]
             Self::Unix(u) => u.accept().await,
             Self::Tcp(t) => t.accept().await,
+            #[cfg(feature = "rustls")]
+            Self::Tls(t) => t.accept().await,
+        }
+    }
+
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(u) => u.set_shutdown(shutdown),
+            Self::Tcp(t) => t.set_shutdown(shutdown),
+            #[cfg(feature = "rustls")]
+            Self::Tls(t) => t.set_shutdown(shutdown),
         }
     }
 
@@ -65,6 +87,8 @@ where
             #[cfg(unix)]
             ParsedListener::Unix(unix) => unix.info(),
             ParsedListener::Tcp(tcp) => tcp.info(),
+            #[cfg(feature = "rustls")]
+            ParsedListener::Tls(tls) => tls.info(),
         }
     }
 }
@@ -0,0 +1,175 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_std::channel::{self, Receiver, Sender};
+use async_std::future::{self, Future};
+use async_std::prelude::*;
+use kv_log_macro::warn;
+
+/// A handle used to gracefully stop a running [`Listener`](crate::listener::Listener).
+///
+/// Once [`Shutdown::trigger`] is called, listeners stop accepting new
+/// connections, wait for in-flight connections to finish and then return
+/// from [`Listener::accept`](crate::listener::Listener::accept). If a drain
+/// timeout has been configured, connections that are still open once it
+/// elapses are dropped.
+///
+/// All clones of a `Shutdown` refer to the same signal.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use async_std::task;
+/// # fn main() -> Result<(), std::io::Error> { task::block_on(async {
+/// #
+/// use std::time::Duration;
+/// use tide::listener::Shutdown;
+///
+/// let mut app = tide::new();
+/// app.at("/").get(|_| async { Ok("Hello, world!") });
+///
+/// let shutdown = Shutdown::new().with_drain_timeout(Duration::from_secs(10));
+/// let handle = shutdown.clone();
+/// task::spawn(async move {
+///     task::sleep(Duration::from_secs(60)).await;
+///     handle.trigger();
+/// });
+///
+/// app.listen_with_shutdown("127.0.0.1:8080", shutdown).await?;
+/// #
+/// # Ok(()) }) }
+/// ```
+#[derive(Clone)]
+pub struct Shutdown {
+    stop: Sender<()>,
+    stopped: Receiver<()>,
+    connections: Arc<Mutex<Option<Sender<()>>>>,
+    drained: Receiver<()>,
+    abort: Sender<()>,
+    aborted: Receiver<()>,
+    drain_timeout: Option<Duration>,
+}
+
+impl Shutdown {
+    /// Create a new `Shutdown` handle.
+    ///
+    /// By default there is no drain timeout: in-flight connections are
+    /// waited on until they close.
+    #[must_use]
+    pub fn new() -> Self {
+        let (stop, stopped) = channel::bounded(1);
+        let (connections, drained) = channel::bounded(1);
+        let (abort, aborted) = channel::bounded(1);
+        Self {
+            stop,
+            stopped,
+            connections: Arc::new(Mutex::new(Some(connections))),
+            drained,
+            abort,
+            aborted,
+            drain_timeout: None,
+        }
+    }
+
+    /// Set the maximum amount of time to wait for in-flight connections to
+    /// finish once shutdown has been triggered.
+    #[must_use]
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = Some(timeout);
+        self
+    }
+
+    /// The configured drain timeout, if any.
+    #[must_use]
+    pub fn drain_timeout(&self) -> Option<Duration> {
+        self.drain_timeout
+    }
+
+    /// Signal listeners to stop accepting new connections.
+    ///
+    /// Calling this more than once has no additional effect.
+    pub fn trigger(&self) {
+        self.stop.close();
+    }
+
+    /// Returns `true` if shutdown has been triggered.
+    #[must_use]
+    pub fn is_triggered(&self) -> bool {
+        self.stop.is_closed()
+    }
+
+    /// Wait until shutdown has been triggered.
+    pub async fn wait(&self) {
+        let _ = self.stopped.recv().await;
+    }
+
+    /// Drive a single connection to completion while tracking it as
+    /// in-flight.
+    ///
+    /// The connection counts as in-flight from the moment `track` is called,
+    /// so listeners should call it before spawning the returned future.
+    /// Resolves to `None` if the connection was dropped because the drain
+    /// timeout elapsed before it finished.
+    ///
+    /// This is intended for implementors of custom
+    /// [`Listener`](crate::listener::Listener)s.
+    pub fn track<F: Future>(&self, connection: F) -> impl Future<Output = Option<F::Output>> {
+        let guard = self.connections.lock().unwrap().clone();
+        let aborted = self.aborted.clone();
+        async move {
+            let _guard = guard;
+            let abort = async move {
+                let _ = aborted.recv().await;
+                None
+            };
+            let connection = async move { Some(connection.await) };
+            connection.race(abort).await
+        }
+    }
+
+    /// Trigger shutdown if it has not been triggered yet, and wait for all
+    /// tracked connections to finish or for the drain timeout to elapse.
+    ///
+    /// This is intended for implementors of custom
+    /// [`Listener`](crate::listener::Listener)s, which should call it before
+    /// returning from `accept`.
+    pub async fn drain(&self) {
+        self.trigger();
+        self.connections.lock().unwrap().take();
+
+        let drained = self.drained.clone();
+        let drain = async move {
+            let _ = drained.recv().await;
+        };
+
+        match self.drain_timeout {
+            Some(timeout) => {
+                if future::timeout(timeout, drain).await.is_err() {
+                    warn!(
+                        "Drain timeout of {:?} elapsed, closing connections",
+                        timeout
+                    );
+                }
+            }
+            None => drain.await,
+        }
+
+        self.abort.close();
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Shutdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shutdown")
+            .field("triggered", &self.is_triggered())
+            .field("drain_timeout", &self.drain_timeout)
+            .finish()
+    }
+}
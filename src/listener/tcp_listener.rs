@@ -1,4 +1,4 @@
-use super::{is_transient_error, ListenInfo};
+use super::{is_transient_error, ConnectionInfo, ConnectionOptions, ListenInfo, Shutdown};
 
 use crate::listener::Listener;
 use crate::Server;
@@ -6,8 +6,9 @@ use crate::Server;
 use std::fmt::{self, Display, Formatter};
 
 use async_std::net::{self, SocketAddr, TcpStream};
-use async_std::prelude::*;
+use async_std::prelude::FutureExt;
 use async_std::{io, task};
+use futures_util::{pin_mut, StreamExt};
 use kv_log_macro::error;
 
 /// This represents a tide [Listener](crate::listener::Listener) that
@@ -16,50 +17,80 @@ use kv_log_macro::error;
 /// from a SocketAddr spec that has not yet been bound OR from a bound
 /// TcpListener.
 ///
-/// This is currently crate-visible only, and tide users are expected
-/// to create these through [ToListener](crate::ToListener) conversions.
+/// Tide users usually create these through [ToListener](crate::ToListener)
+/// conversions, and only need to name this type to set its
+/// [`ConnectionOptions`].
 pub struct TcpListener<State> {
     addrs: Option<Vec<SocketAddr>>,
     listener: Option<net::TcpListener>,
     server: Option<Server<State>>,
     info: Option<ListenInfo>,
+    shutdown: Shutdown,
+    options: ConnectionOptions,
 }
 
 impl<State> TcpListener<State> {
+    /// Create a new instance of `TcpListener` that binds to `addrs`, using
+    /// the first address that can be bound.
     pub fn from_addrs(addrs: Vec<SocketAddr>) -> Self {
         Self {
             addrs: Some(addrs),
             listener: None,
             server: None,
             info: None,
+            shutdown: Shutdown::new(),
+            options: ConnectionOptions::default(),
         }
     }
 
+    /// Create a new instance of `TcpListener` from an already bound
+    /// listener.
     pub fn from_listener(tcp_listener: impl Into<net::TcpListener>) -> Self {
         Self {
             addrs: None,
             listener: Some(tcp_listener.into()),
             server: None,
             info: None,
+            shutdown: Shutdown::new(),
+            options: ConnectionOptions::default(),
         }
     }
+
+    /// Set the options connections accepted by this listener are served
+    /// with.
+    #[must_use]
+    pub fn with_options(mut self, options: ConnectionOptions) -> Self {
+        self.options = options;
+        self
+    }
 }
 
-fn handle_tcp<State: Clone + Send + Sync + 'static>(app: Server<State>, stream: TcpStream) {
-    task::spawn(async move {
-        let local_addr = stream.local_addr().ok();
-        let peer_addr = stream.peer_addr().ok();
+fn handle_tcp<State: Clone + Send + Sync + 'static>(
+    app: Server<State>,
+    stream: TcpStream,
+    shutdown: Shutdown,
+    options: ConnectionOptions,
+) {
+    let connection = shutdown.clone().track(async move {
+        if options.nodelay {
+            if let Err(error) = stream.set_nodelay(true) {
+                error!("Could not set TCP_NODELAY", { error: error.to_string() });
+            }
+        }
 
-        let fut = async_h1::accept(stream, |mut req| async {
-            req.set_local_addr(local_addr);
-            req.set_peer_addr(peer_addr);
-            app.respond(req).await
-        });
+        let mut info = ConnectionInfo::new().shutdown(shutdown).options(options);
+        if let Ok(local_addr) = stream.local_addr() {
+            info = info.local_addr(local_addr);
+        }
+        if let Ok(peer_addr) = stream.peer_addr() {
+            info = info.peer_addr(peer_addr);
+        }
 
-        if let Err(error) = fut.await {
+        if let Err(error) = app.serve_h1(stream, info).await {
             error!("async-h1 error", { error: error.to_string() });
         }
     });
+    task::spawn(connection);
 }
 
 #[async_trait::async_trait]
@@ -84,7 +115,11 @@ where
         let conn_string = format!("{}", self);
         let transport = "tcp".to_owned();
         let tls = false;
-        self.info = Some(ListenInfo::new(conn_string, transport, tls));
+        let mut info = ListenInfo::new(conn_string, transport, tls);
+        if let Some(listener) = &self.listener {
+            info = info.with_local_addr(listener.local_addr()?);
+        }
+        self.info = Some(info);
 
         Ok(())
     }
@@ -99,26 +134,40 @@ where
             .take()
             .expect("`Listener::bind` must be called before `Listener::accept`");
 
-        let mut incoming = listener.incoming();
+        let shutdown = self.shutdown.clone();
+        let incoming = listener.incoming().take_until(shutdown.wait());
+        pin_mut!(incoming);
 
         while let Some(stream) = incoming.next().await {
             match stream {
                 Err(ref e) if is_transient_error(e) => continue,
                 Err(error) => {
-                    let delay = std::time::Duration::from_millis(500);
+                    let delay = self.options.accept_backoff;
                     error!("Error: {}. Pausing for {:?}.", error, delay);
-                    task::sleep(delay).await;
+                    // Do not hold up shutdown while pausing.
+                    task::sleep(delay).race(shutdown.wait()).await;
                     continue;
                 }
 
                 Ok(stream) => {
-                    handle_tcp(server.clone(), stream);
+                    handle_tcp(
+                        server.clone(),
+                        stream,
+                        shutdown.clone(),
+                        self.options.clone(),
+                    );
                 }
             };
         }
+
+        shutdown.drain().await;
         Ok(())
     }
 
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.shutdown = shutdown;
+    }
+
     fn info(&self) -> Vec<ListenInfo> {
         match &self.info {
             Some(info) => vec![info.clone()],
@@ -0,0 +1,486 @@
+use super::{is_transient_error, ConnectionInfo, ConnectionOptions, ListenInfo, Shutdown};
+
+use crate::listener::Listener;
+use crate::Server;
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_std::net::{self, SocketAddr, TcpStream};
+use async_std::prelude::FutureExt;
+use async_std::{fs, future, io, task};
+use futures_rustls::rustls::ServerConfig;
+use futures_rustls::TlsAcceptor;
+use futures_util::{pin_mut, StreamExt};
+use kv_log_macro::error;
+
+/// This represents a tide [Listener](crate::listener::Listener) that
+/// wraps an [async_std::net::TcpListener] and terminates TLS using
+/// [rustls](https://docs.rs/rustls).
+///
+/// Requests served by this listener have their URL scheme set to `https`.
+/// Connections are served with the [`ConnectionOptions`] given to
+/// [`TlsListenerBuilder::options`], once their TLS handshake completes within
+/// the [handshake timeout](TlsListenerBuilder::handshake_timeout).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use async_std::task::block_on;
+/// # fn main() -> Result<(), std::io::Error> { block_on(async {
+/// #
+/// use tide::listener::TlsListener;
+///
+/// let mut app = tide::new();
+/// app.at("/").get(|_| async { Ok("Hello, world!") });
+/// app.listen(
+///     TlsListener::build()
+///         .addrs("localhost:4433")
+///         .cert("./tls/localhost-4433.cert")
+///         .key("./tls/localhost-4433.key"),
+/// )
+/// .await?;
+/// #
+/// # Ok(()) }) }
+/// ```
+///
+/// TLS listeners can also be parsed from `https://`, `tls://` and `ssl://`
+/// urls, with the paths of the PEM encoded certificate chain and private key
+/// passed as `cert` and `key` query parameters:
+///
+/// ```no_run
+/// # use async_std::task::block_on;
+/// # fn main() -> Result<(), std::io::Error> { block_on(async {
+/// # let app = tide::new();
+/// app.listen("https://localhost:4433?cert=./tls/cert.pem&key=./tls/key.pem").await?;
+/// # Ok(()) }) }
+/// ```
+pub struct TlsListener<State> {
+    addrs: Option<Vec<SocketAddr>>,
+    listener: Option<net::TcpListener>,
+    config: TlsConfig,
+    server: Option<Server<State>>,
+    info: Option<ListenInfo>,
+    shutdown: Shutdown,
+    options: ConnectionOptions,
+    handshake_timeout: Duration,
+}
+
+/// How long a client may take to complete the TLS handshake by default.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum TlsConfig {
+    Paths { cert: PathBuf, key: PathBuf },
+    ServerConfig(Arc<ServerConfig>),
+    Acceptor(TlsAcceptor),
+}
+
+impl Debug for TlsConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Paths { cert, key } => f
+                .debug_struct("Paths")
+                .field("cert", cert)
+                .field("key", key)
+                .finish(),
+            Self::ServerConfig(_) => write!(f, "ServerConfig"),
+            Self::Acceptor(_) => write!(f, "Acceptor"),
+        }
+    }
+}
+
+impl TlsConfig {
+    async fn acceptor(&self) -> io::Result<TlsAcceptor> {
+        match self {
+            Self::Paths { cert, key } => {
+                let config = load_server_config(cert, key).await?;
+                Ok(TlsAcceptor::from(Arc::new(config)))
+            }
+            Self::ServerConfig(config) => Ok(TlsAcceptor::from(config.clone())),
+            Self::Acceptor(acceptor) => Ok(acceptor.clone()),
+        }
+    }
+}
+
+async fn load_server_config(cert: &Path, key: &Path) -> io::Result<ServerConfig> {
+    let certs = fs::read(cert).await?;
+    let certs = rustls_pemfile::certs(&mut certs.as_slice()).collect::<io::Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("no certificates found in `{}`", cert.display()),
+        ));
+    }
+
+    let key_bytes = fs::read(key).await?;
+    let key = rustls_pemfile::private_key(&mut key_bytes.as_slice())?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("no private key found in `{}`", key.display()),
+        )
+    })?;
+
+    ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+impl<State> TlsListener<State> {
+    /// Start building a new `TlsListener`. See [`TlsListenerBuilder`] for
+    /// the available options.
+    #[must_use]
+    pub fn build() -> TlsListenerBuilder<State> {
+        TlsListenerBuilder::new()
+    }
+
+    fn new(
+        addrs: Option<Vec<SocketAddr>>,
+        listener: Option<net::TcpListener>,
+        config: TlsConfig,
+        options: ConnectionOptions,
+        handshake_timeout: Duration,
+    ) -> Self {
+        Self {
+            addrs,
+            listener,
+            config,
+            server: None,
+            info: None,
+            shutdown: Shutdown::new(),
+            options,
+            handshake_timeout,
+        }
+    }
+}
+
+fn handle_tls<State: Clone + Send + Sync + 'static>(
+    app: Server<State>,
+    stream: TcpStream,
+    acceptor: TlsAcceptor,
+    shutdown: Shutdown,
+    options: ConnectionOptions,
+    handshake_timeout: Duration,
+) {
+    let connection = shutdown.clone().track(async move {
+        if options.nodelay {
+            if let Err(error) = stream.set_nodelay(true) {
+                error!("Could not set TCP_NODELAY", { error: error.to_string() });
+            }
+        }
+
+        let local_addr = stream.local_addr().ok();
+        let peer_addr = stream.peer_addr().ok();
+
+        let mut info = ConnectionInfo::new()
+            .tls(true)
+            .shutdown(shutdown.clone())
+            .options(options);
+        if let Some(local_addr) = local_addr {
+            info = info.local_addr(local_addr);
+        }
+        if let Some(peer_addr) = peer_addr {
+            info = info.peer_addr(peer_addr);
+        }
+
+        let stream = match future::timeout(handshake_timeout, acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(error)) => {
+                error!("TLS handshake error", { error: error.to_string() });
+                return;
+            }
+            Err(_) => {
+                error!("TLS handshake timed out", { timeout: format!("{:?}", handshake_timeout) });
+                return;
+            }
+        };
+        let stream = async_dup::Arc::new(async_dup::Mutex::new(stream));
+        if let Err(error) = app.serve_h1(stream, info).await {
+            error!("async-h1 error", { error: error.to_string() });
+        }
+    });
+    task::spawn(connection);
+}
+
+#[async_trait::async_trait]
+impl<State> Listener<State> for TlsListener<State>
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn bind(&mut self, server: Server<State>) -> io::Result<()> {
+        assert!(self.server.is_none(), "`bind` should only be called once");
+        self.server = Some(server);
+
+        let acceptor = self.config.acceptor().await?;
+        self.config = TlsConfig::Acceptor(acceptor);
+
+        if self.listener.is_none() {
+            let addrs = self
+                .addrs
+                .take()
+                .expect("`bind` should only be called once");
+            let listener = net::TcpListener::bind(addrs.as_slice()).await?;
+            self.listener = Some(listener);
+        }
+
+        // Format the listen information.
+        let conn_string = format!("{}", self);
+        let transport = "tcp".to_owned();
+        let tls = true;
+        let mut info = ListenInfo::new(conn_string, transport, tls);
+        if let Some(listener) = &self.listener {
+            info = info.with_local_addr(listener.local_addr()?);
+        }
+        self.info = Some(info);
+
+        Ok(())
+    }
+
+    async fn accept(&mut self) -> io::Result<()> {
+        let server = self
+            .server
+            .take()
+            .expect("`Listener::bind` must be called before `Listener::accept`");
+        let listener = self
+            .listener
+            .take()
+            .expect("`Listener::bind` must be called before `Listener::accept`");
+        let acceptor = match &self.config {
+            TlsConfig::Acceptor(acceptor) => acceptor.clone(),
+            _ => panic!("`Listener::bind` must be called before `Listener::accept`"),
+        };
+
+        let shutdown = self.shutdown.clone();
+        let incoming = listener.incoming().take_until(shutdown.wait());
+        pin_mut!(incoming);
+
+        while let Some(stream) = incoming.next().await {
+            match stream {
+                Err(ref e) if is_transient_error(e) => continue,
+                Err(error) => {
+                    let delay = self.options.accept_backoff;
+                    error!("Error: {}. Pausing for {:?}.", error, delay);
+                    // Do not hold up shutdown while pausing.
+                    task::sleep(delay).race(shutdown.wait()).await;
+                    continue;
+                }
+
+                Ok(stream) => {
+                    handle_tls(
+                        server.clone(),
+                        stream,
+                        acceptor.clone(),
+                        shutdown.clone(),
+                        self.options.clone(),
+                        self.handshake_timeout,
+                    );
+                }
+            };
+        }
+
+        shutdown.drain().await;
+        Ok(())
+    }
+
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.shutdown = shutdown;
+    }
+
+    fn info(&self) -> Vec<ListenInfo> {
+        match &self.info {
+            Some(info) => vec![info.clone()],
+            None => vec![],
+        }
+    }
+}
+
+impl<State> Debug for TlsListener<State> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsListener")
+            .field("listener", &self.listener)
+            .field("addrs", &self.addrs)
+            .field("config", &self.config)
+            .field(
+                "server",
+                if self.server.is_some() {
+                    &"Some(Server<State>)"
+                } else {
+                    &"None"
+                },
+            )
+            .finish()
+    }
+}
+
+impl<State> Display for TlsListener<State> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let https_fmt = |a| format!("https://{}", a);
+        match &self.listener {
+            Some(listener) => {
+                let addr = listener.local_addr().expect("Could not get local addr");
+                write!(f, "{}", https_fmt(&addr))
+            }
+            None => match &self.addrs {
+                Some(addrs) => {
+                    let addrs = addrs.iter().map(https_fmt).collect::<Vec<_>>().join(", ");
+                    write!(f, "{}", addrs)
+                }
+                None => write!(f, "Not listening. Did you forget to call `Listener::bind`?"),
+            },
+        }
+    }
+}
+
+/// A builder for [`TlsListener`].
+///
+/// Exactly one source of addresses ([`addrs`](Self::addrs) or
+/// [`tcp`](Self::tcp)) and one source of TLS configuration (both
+/// [`cert`](Self::cert) and [`key`](Self::key), or
+/// [`config`](Self::config)) must be provided.
+pub struct TlsListenerBuilder<State> {
+    addrs: Option<io::Result<Vec<SocketAddr>>>,
+    listener: Option<net::TcpListener>,
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+    config: Option<Arc<ServerConfig>>,
+    options: ConnectionOptions,
+    handshake_timeout: Duration,
+    _state: std::marker::PhantomData<State>,
+}
+
+impl<State> TlsListenerBuilder<State> {
+    fn new() -> Self {
+        Self {
+            addrs: None,
+            listener: None,
+            cert: None,
+            key: None,
+            config: None,
+            options: ConnectionOptions::default(),
+            handshake_timeout: HANDSHAKE_TIMEOUT,
+            _state: std::marker::PhantomData,
+        }
+    }
+
+    /// Provide the addresses to bind to.
+    ///
+    /// Address resolution happens immediately; resolution errors are
+    /// returned from [`finish`](Self::finish).
+    #[must_use]
+    pub fn addrs(mut self, addrs: impl std::net::ToSocketAddrs) -> Self {
+        self.addrs = Some(addrs.to_socket_addrs().map(Iterator::collect));
+        self
+    }
+
+    /// Provide an already bound tcp listener.
+    #[must_use]
+    pub fn tcp(mut self, listener: impl Into<net::TcpListener>) -> Self {
+        self.listener = Some(listener.into());
+        self
+    }
+
+    /// Provide the path to a PEM encoded certificate chain.
+    #[must_use]
+    pub fn cert(mut self, path: impl AsRef<Path>) -> Self {
+        self.cert = Some(path.as_ref().into());
+        self
+    }
+
+    /// Provide the path to a PEM encoded private key.
+    #[must_use]
+    pub fn key(mut self, path: impl AsRef<Path>) -> Self {
+        self.key = Some(path.as_ref().into());
+        self
+    }
+
+    /// Provide a prebuilt rustls [`ServerConfig`].
+    #[must_use]
+    pub fn config(mut self, config: impl Into<Arc<ServerConfig>>) -> Self {
+        self.config = Some(config.into());
+        self
+    }
+
+    /// Set the options connections accepted by this listener are served
+    /// with.
+    #[must_use]
+    pub fn options(mut self, options: ConnectionOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set how long a client may take to complete the TLS handshake, after
+    /// which the connection is closed. Defaults to 10 seconds.
+    #[must_use]
+    pub fn handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Finish building the listener.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the builder is missing addresses or TLS
+    /// configuration, or was given conflicting options.
+    pub fn finish(self) -> io::Result<TlsListener<State>> {
+        let config = match (self.cert, self.key, self.config) {
+            (Some(cert), Some(key), None) => TlsConfig::Paths { cert, key },
+            (None, None, Some(config)) => TlsConfig::ServerConfig(config),
+            (None, None, None) => {
+                return Err(invalid_input(
+                    "TlsListener requires either a cert and key or a ServerConfig",
+                ))
+            }
+            (_, _, Some(_)) => {
+                return Err(invalid_input(
+                    "TlsListener cannot be given both a ServerConfig and a cert or key",
+                ))
+            }
+            _ => return Err(invalid_input("TlsListener requires both a cert and a key")),
+        };
+
+        let (options, timeout) = (self.options, self.handshake_timeout);
+        match (self.addrs, self.listener) {
+            (Some(addrs), None) => Ok(TlsListener::new(
+                Some(addrs?),
+                None,
+                config,
+                options,
+                timeout,
+            )),
+            (None, Some(listener)) => Ok(TlsListener::new(
+                None,
+                Some(listener),
+                config,
+                options,
+                timeout,
+            )),
+            (None, None) => Err(invalid_input(
+                "TlsListener requires either addrs or a tcp listener",
+            )),
+            (Some(_), Some(_)) => Err(invalid_input(
+                "TlsListener cannot be given both addrs and a tcp listener",
+            )),
+        }
+    }
+}
+
+impl<State> Debug for TlsListenerBuilder<State> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsListenerBuilder")
+            .field("addrs", &self.addrs)
+            .field("listener", &self.listener)
+            .field("cert", &self.cert)
+            .field("key", &self.key)
+            .field("config", &self.config.as_ref().map(|_| "ServerConfig"))
+            .field("options", &self.options)
+            .field("handshake_timeout", &self.handshake_timeout)
+            .finish()
+    }
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
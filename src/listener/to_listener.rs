@@ -20,6 +20,10 @@ use async_std::io;
 /// * `http+unix://./socket.file` (also relative path)
 /// * `http+unix://../socket` (relative path)
 ///
+/// # Strings supported only with the `rustls` feature:
+/// * `https://localhost:4433?cert=cert.pem&key=key.pem` (binds to port 443 by default)
+/// * `tls://0.0.0.0:4433?cert=cert.pem&key=key.pem` (`tls` and `ssl` are aliases for https)
+///
 /// # String supported only on windows:
 /// * `:3000` (binds to port 3000)
 ///
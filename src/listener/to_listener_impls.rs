@@ -1,6 +1,8 @@
 #[cfg(unix)]
 use super::UnixListener;
 use super::{ConcurrentListener, FailoverListener, ParsedListener, TcpListener, ToListener};
+#[cfg(feature = "rustls")]
+use super::{TlsListener, TlsListenerBuilder};
 use crate::http::url::Url;
 use async_std::io;
 use std::net::ToSocketAddrs;
@@ -38,10 +40,38 @@ where
                 self.socket_addrs(|| Some(80))?,
             ))),
 
-            "tls" | "ssl" | "https" => Err(io::Error::new(
-                io::ErrorKind::Other,
-                "parsing TLS listeners not supported yet",
-            )),
+            "tls" | "ssl" | "https" => {
+                #[cfg(feature = "rustls")]
+                {
+                    let query_param = |name| {
+                        self.query_pairs()
+                            .find(|(key, _)| key == name)
+                            .map(|(_, value)| value.into_owned())
+                    };
+
+                    match (query_param("cert"), query_param("key")) {
+                        (Some(cert), Some(key)) => Ok(ParsedListener::Tls(
+                            TlsListener::build()
+                                .addrs(self.socket_addrs(|| Some(443))?.as_slice())
+                                .cert(cert)
+                                .key(key)
+                                .finish()?,
+                        )),
+                        _ => Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "TLS listener urls require `cert` and `key` query parameters",
+                        )),
+                    }
+                }
+
+                #[cfg(not(feature = "rustls"))]
+                {
+                    Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        "parsing TLS listeners not supported yet",
+                    ))
+                }
+            }
 
             _ => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
@@ -209,6 +239,28 @@ where
     }
 }
 
+#[cfg(feature = "rustls")]
+impl<State> ToListener<State> for TlsListener<State>
+where
+    State: Clone + Send + Sync + 'static,
+{
+    type Listener = Self;
+    fn to_listener(self) -> io::Result<Self::Listener> {
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "rustls")]
+impl<State> ToListener<State> for TlsListenerBuilder<State>
+where
+    State: Clone + Send + Sync + 'static,
+{
+    type Listener = TlsListener<State>;
+    fn to_listener(self) -> io::Result<Self::Listener> {
+        self.finish()
+    }
+}
+
 impl<State> ToListener<State> for ConcurrentListener<State>
 where
     State: Clone + Send + Sync + 'static,
@@ -339,6 +391,7 @@ mod parse_tests {
         }
     }
 
+    #[cfg(not(feature = "rustls"))]
     #[test]
     fn str_tls_parse_and_url() {
         let err = listen("tls://localhost:443").unwrap_err();
@@ -348,6 +401,23 @@ mod parse_tests {
         assert_eq!(err.to_string(), "parsing TLS listeners not supported yet");
     }
 
+    #[cfg(feature = "rustls")]
+    #[test]
+    fn str_tls_parse_and_url() {
+        let listener = listen("tls://localhost:4433?cert=cert.pem&key=key.pem").unwrap();
+        assert!(listener.to_string().contains("https://127.0.0.1:4433"));
+
+        let listener =
+            listen(Url::parse("https://127.0.0.1?cert=cert.pem&key=key.pem").unwrap()).unwrap();
+        assert_eq!(listener.to_string(), "https://127.0.0.1:443");
+
+        let err = listen("https://localhost:4433").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TLS listener urls require `cert` and `key` query parameters"
+        );
+    }
+
     #[test]
     fn str_unknown_scheme() {
         let err = listen("pigeon://localhost:443").unwrap_err();
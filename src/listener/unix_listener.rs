@@ -1,4 +1,4 @@
-use super::{is_transient_error, ListenInfo};
+use super::{is_transient_error, ConnectionInfo, ConnectionOptions, ListenInfo, Shutdown};
 
 use crate::listener::Listener;
 use crate::Server;
@@ -7,8 +7,9 @@ use std::fmt::{self, Display, Formatter};
 
 use async_std::os::unix::net::{self, SocketAddr, UnixStream};
 use async_std::path::PathBuf;
-use async_std::prelude::*;
+use async_std::prelude::FutureExt;
 use async_std::{io, task};
+use futures_util::{pin_mut, StreamExt};
 use kv_log_macro::error;
 
 /// This represents a tide [Listener](crate::listener::Listener) that
@@ -17,50 +18,74 @@ use kv_log_macro::error;
 /// from a [`PathBuf`] spec that has not yet been bound OR from a bound
 /// [async_std::os::unix::net::UnixListener].
 ///
-/// This is currently crate-visible only, and tide users are expected
-/// to create these through [ToListener](crate::ToListener) conversions.
+/// Tide users usually create these through [ToListener](crate::ToListener)
+/// conversions, and only need to name this type to set its
+/// [`ConnectionOptions`].
 pub struct UnixListener<State> {
     path: Option<PathBuf>,
     listener: Option<net::UnixListener>,
     server: Option<Server<State>>,
     info: Option<ListenInfo>,
+    shutdown: Shutdown,
+    options: ConnectionOptions,
 }
 
 impl<State> UnixListener<State> {
+    /// Create a new instance of `UnixListener` that binds to the socket at
+    /// `path`.
     pub fn from_path(path: impl Into<PathBuf>) -> Self {
         Self {
             path: Some(path.into()),
             listener: None,
             server: None,
             info: None,
+            shutdown: Shutdown::new(),
+            options: ConnectionOptions::default(),
         }
     }
 
+    /// Create a new instance of `UnixListener` from an already bound
+    /// listener.
     pub fn from_listener(unix_listener: impl Into<net::UnixListener>) -> Self {
         Self {
             path: None,
             listener: Some(unix_listener.into()),
             server: None,
             info: None,
+            shutdown: Shutdown::new(),
+            options: ConnectionOptions::default(),
         }
     }
-}
 
-fn handle_unix<State: Clone + Send + Sync + 'static>(app: Server<State>, stream: UnixStream) {
-    task::spawn(async move {
-        let local_addr = unix_socket_addr_to_string(stream.local_addr());
-        let peer_addr = unix_socket_addr_to_string(stream.peer_addr());
+    /// Set the options connections accepted by this listener are served
+    /// with.
+    #[must_use]
+    pub fn with_options(mut self, options: ConnectionOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
 
-        let fut = async_h1::accept(stream, |mut req| async {
-            req.set_local_addr(local_addr.as_ref());
-            req.set_peer_addr(peer_addr.as_ref());
-            app.respond(req).await
-        });
+fn handle_unix<State: Clone + Send + Sync + 'static>(
+    app: Server<State>,
+    stream: UnixStream,
+    shutdown: Shutdown,
+    options: ConnectionOptions,
+) {
+    let connection = shutdown.clone().track(async move {
+        let mut info = ConnectionInfo::new().shutdown(shutdown).options(options);
+        if let Some(local_addr) = unix_socket_addr_to_string(stream.local_addr()) {
+            info = info.local_addr(local_addr);
+        }
+        if let Some(peer_addr) = unix_socket_addr_to_string(stream.peer_addr()) {
+            info = info.peer_addr(peer_addr);
+        }
 
-        if let Err(error) = fut.await {
+        if let Err(error) = app.serve_h1(stream, info).await {
             error!("async-h1 error", { error: error.to_string() });
         }
     });
+    task::spawn(connection);
 }
 
 #[async_trait::async_trait]
@@ -82,7 +107,13 @@ where
         let conn_string = format!("{}", self);
         let transport = "uds".to_owned();
         let tls = false;
-        self.info = Some(ListenInfo::new(conn_string, transport, tls));
+        let mut info = ListenInfo::new(conn_string, transport, tls);
+        if let Some(listener) = &self.listener {
+            if let Some(path) = listener.local_addr()?.as_pathname() {
+                info = info.with_local_addr(path.to_path_buf());
+            }
+        }
+        self.info = Some(info);
 
         Ok(())
     }
@@ -97,26 +128,40 @@ where
             .take()
             .expect("`Listener::bind` must be called before `Listener::accept`");
 
-        let mut incoming = listener.incoming();
+        let shutdown = self.shutdown.clone();
+        let incoming = listener.incoming().take_until(shutdown.wait());
+        pin_mut!(incoming);
 
         while let Some(stream) = incoming.next().await {
             match stream {
                 Err(ref e) if is_transient_error(e) => continue,
                 Err(error) => {
-                    let delay = std::time::Duration::from_millis(500);
+                    let delay = self.options.accept_backoff;
                     error!("Error: {}. Pausing for {:?}.", error, delay);
-                    task::sleep(delay).await;
+                    // Do not hold up shutdown while pausing.
+                    task::sleep(delay).race(shutdown.wait()).await;
                     continue;
                 }
 
                 Ok(stream) => {
-                    handle_unix(server.clone(), stream);
+                    handle_unix(
+                        server.clone(),
+                        stream,
+                        shutdown.clone(),
+                        self.options.clone(),
+                    );
                 }
             };
         }
+
+        shutdown.drain().await;
         Ok(())
     }
 
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.shutdown = shutdown;
+    }
+
     fn info(&self) -> Vec<ListenInfo> {
         match &self.info {
             Some(info) => vec![info.clone()],
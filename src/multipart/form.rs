@@ -0,0 +1,226 @@
+use async_std::fs::File;
+use async_std::io::{self, prelude::*};
+use async_std::task;
+use tempfile::NamedTempFile;
+
+use std::path::Path;
+
+use super::Multipart;
+use crate::http::{Mime, StatusCode};
+
+/// Size limits applied by [`Multipart::into_form`].
+///
+/// Exceeding any of them fails with `413 Payload Too Large`.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    field_size: u64,
+    file_size: u64,
+    total_size: u64,
+}
+
+impl Limits {
+    /// Create the default limits: 64 KiB per text field, 8 MiB per file and
+    /// 16 MiB in total.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            field_size: 64 * 1024,
+            file_size: 8 * 1024 * 1024,
+            total_size: 16 * 1024 * 1024,
+        }
+    }
+
+    /// Set the maximum size of a single text field, which is buffered in
+    /// memory.
+    #[must_use]
+    pub fn field_size(mut self, limit: u64) -> Self {
+        self.field_size = limit;
+        self
+    }
+
+    /// Set the maximum size of a single file, which is spooled to disk.
+    #[must_use]
+    pub fn file_size(mut self, limit: u64) -> Self {
+        self.file_size = limit;
+        self
+    }
+
+    /// Set the maximum combined size of all fields and files.
+    #[must_use]
+    pub fn total_size(mut self, limit: u64) -> Self {
+        self.total_size = limit;
+        self
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A buffered `multipart/form-data` body.
+///
+/// This `struct` is created by [`Multipart::into_form`]. Parts with a file
+/// name are stored as [`UploadedFile`]s, all others as text fields.
+#[derive(Debug, Default)]
+pub struct MultipartForm {
+    fields: Vec<(String, String)>,
+    files: Vec<(String, UploadedFile)>,
+}
+
+impl MultipartForm {
+    /// Get the value of the first text field called `name`.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// An iterator over all text fields, in the order they were sent.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Get the first file uploaded as `name`.
+    #[must_use]
+    pub fn file(&self, name: &str) -> Option<&UploadedFile> {
+        self.files
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, file)| file)
+    }
+
+    /// Remove and return the first file uploaded as `name`.
+    pub fn take_file(&mut self, name: &str) -> Option<UploadedFile> {
+        let index = self.files.iter().position(|(field, _)| field == name)?;
+        Some(self.files.remove(index).1)
+    }
+
+    /// An iterator over all uploaded files, in the order they were sent.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &UploadedFile)> {
+        self.files.iter().map(|(name, file)| (name.as_str(), file))
+    }
+}
+
+/// A file uploaded as part of a [`MultipartForm`].
+///
+/// The file is stored in a temporary file that is deleted when this value is
+/// dropped, unless it is [persisted](UploadedFile::persist).
+#[derive(Debug)]
+pub struct UploadedFile {
+    file_name: Option<String>,
+    content_type: Option<Mime>,
+    size: u64,
+    file: NamedTempFile,
+}
+
+impl UploadedFile {
+    /// The file name sent by the client.
+    ///
+    /// This is not sanitized, and should not be used as a path as-is.
+    #[must_use]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// The content type sent by the client.
+    #[must_use]
+    pub fn content_type(&self) -> Option<&Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// The size of the file in bytes.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The path of the temporary file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Move the temporary file to `path`, so it is not deleted.
+    pub fn persist(self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.file.persist(path).map(|_| ()).map_err(|err| err.error)
+    }
+}
+
+impl Multipart {
+    /// Read all parts, buffering text fields in memory and spooling files to
+    /// temporary files.
+    ///
+    /// # Errors
+    ///
+    /// A `413 Payload Too Large` error is returned if any of the `limits`
+    /// are exceeded, and a `400 Bad Request` error if the body is malformed
+    /// or a text field is not valid UTF-8.
+    pub async fn into_form(mut self, limits: Limits) -> crate::Result<MultipartForm> {
+        let mut form = MultipartForm::default();
+        let mut total = 0;
+
+        while let Some(mut part) = self.next_part().await? {
+            let name = part.name().unwrap_or_default().to_owned();
+            let mut size = 0;
+            let mut count = |len: usize, limit: u64| {
+                size += len as u64;
+                total += len as u64;
+                if size > limit {
+                    Err(too_large(&format!("multipart field `{}`", name), limit))
+                } else if total > limits.total_size {
+                    Err(too_large("multipart body", limits.total_size))
+                } else {
+                    Ok(())
+                }
+            };
+
+            if part.file_name().is_some() {
+                let file_name = part.file_name().map(ToOwned::to_owned);
+                let content_type = part.content_type();
+                let (temp, file) = task::spawn_blocking(|| -> io::Result<_> {
+                    let temp = NamedTempFile::new()?;
+                    let file = temp.reopen()?;
+                    Ok((temp, file))
+                })
+                .await?;
+                let mut file = File::from(file);
+                while let Some(chunk) = part.chunk().await? {
+                    count(chunk.len(), limits.file_size)?;
+                    file.write_all(&chunk).await?;
+                }
+                file.flush().await?;
+                let file = UploadedFile {
+                    file_name,
+                    content_type,
+                    size,
+                    file: temp,
+                };
+                form.files.push((name, file));
+            } else {
+                let mut value = Vec::new();
+                while let Some(chunk) = part.chunk().await? {
+                    count(chunk.len(), limits.field_size)?;
+                    value.extend_from_slice(&chunk);
+                }
+                let value = String::from_utf8(value)
+                    .map_err(|err| crate::Error::new(StatusCode::BadRequest, err))?;
+                form.fields.push((name, value));
+            }
+        }
+
+        Ok(form)
+    }
+}
+
+fn too_large(what: &str, limit: u64) -> crate::Error {
+    crate::Error::from_str(
+        StatusCode::PayloadTooLarge,
+        format!("{} exceeds the limit of {} bytes", what, limit),
+    )
+}
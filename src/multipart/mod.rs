@@ -0,0 +1,257 @@
+//! `multipart/form-data` request bodies.
+//!
+//! [`Request::body_multipart`](crate::Request::body_multipart) returns a
+//! [`Multipart`], which yields the parts of the body one at a time as they
+//! arrive. Each [`Part`] can be read as a stream of bytes, so large uploads
+//! never need to be held in memory.
+//!
+//! For the common case of HTML forms, [`Multipart::into_form`] buffers text
+//! fields in memory and spools file uploads to temporary files, enforcing the
+//! size [`Limits`] it is given.
+//!
+//! # Examples
+//!
+//! Streaming parts:
+//!
+//! ```no_run
+//! # use async_std::task::block_on;
+//! # fn main() -> Result<(), std::io::Error> { block_on(async {
+//! #
+//! use async_std::io;
+//!
+//! let mut app = tide::new();
+//! app.at("/upload").post(|mut req: tide::Request<()>| async move {
+//!     let mut multipart = req.body_multipart()?;
+//!     let mut total = 0;
+//!     while let Some(part) = multipart.next_part().await? {
+//!         total += io::copy(part, io::sink()).await?;
+//!     }
+//!     Ok(format!("received {} bytes", total))
+//! });
+//! app.listen("127.0.0.1:8080").await?;
+//! #
+//! # Ok(()) }) }
+//! ```
+//!
+//! Buffering a form:
+//!
+//! ```no_run
+//! # use async_std::task::block_on;
+//! # fn main() -> Result<(), std::io::Error> { block_on(async {
+//! #
+//! use tide::multipart::Limits;
+//!
+//! let mut app = tide::new();
+//! app.at("/avatar").post(|mut req: tide::Request<()>| async move {
+//!     let mut form = req
+//!         .body_multipart()?
+//!         .into_form(Limits::new().file_size(1024 * 1024))
+//!         .await?;
+//!     let user = form.field("user").unwrap_or("anonymous").to_owned();
+//!     if let Some(avatar) = form.take_file("avatar") {
+//!         avatar.persist(format!("/var/avatars/{}", user))?;
+//!     }
+//!     Ok("saved")
+//! });
+//! app.listen("127.0.0.1:8080").await?;
+//! #
+//! # Ok(()) }) }
+//! ```
+
+mod form;
+
+pub use form::{Limits, MultipartForm, UploadedFile};
+
+use async_std::io::{self, prelude::*};
+use async_std::task::{Context, Poll};
+use futures_util::stream::{self, Stream};
+use multer::bytes::Bytes;
+
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+
+use crate::http::{Body, Mime, StatusCode};
+use crate::security::body_error;
+
+/// A streaming `multipart/form-data` body.
+///
+/// This `struct` is created by
+/// [`Request::body_multipart`](crate::Request::body_multipart).
+pub struct Multipart {
+    inner: multer::Multipart<'static>,
+}
+
+impl Multipart {
+    /// Create a `Multipart` from a body and the `Content-Type` it was sent
+    /// with.
+    pub(crate) fn new(body: Body, content_type: Option<&str>) -> crate::Result<Self> {
+        let boundary =
+            multer::parse_boundary(content_type.unwrap_or_default()).map_err(multipart_error)?;
+        let stream = stream::unfold(body, |mut body| async move {
+            let mut buf = vec![0; 8 * 1024];
+            match body.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(buf), body))
+                }
+                Err(err) => Some((Err(err), body)),
+            }
+        });
+        Ok(Self {
+            inner: multer::Multipart::new(stream, boundary),
+        })
+    }
+
+    /// Get the next part of the body, or `None` once all parts have been
+    /// read.
+    ///
+    /// Any unread data of the previous part is skipped.
+    ///
+    /// # Errors
+    ///
+    /// A `400 Bad Request` error is returned if the body is malformed.
+    pub async fn next_part(&mut self) -> crate::Result<Option<Part>> {
+        let field = self.inner.next_field().await.map_err(multipart_error)?;
+        Ok(field.map(|field| Part {
+            field,
+            buf: Bytes::new(),
+        }))
+    }
+}
+
+impl Debug for Multipart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Multipart").finish()
+    }
+}
+
+/// A single part of a `multipart/form-data` body.
+///
+/// The part's contents can be read through its [`Read`] and [`BufRead`]
+/// implementations, or all at once with [`Part::bytes`] and [`Part::text`].
+pub struct Part {
+    field: multer::Field<'static>,
+    buf: Bytes,
+}
+
+impl Part {
+    /// The name of the form field this part belongs to.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.field.name()
+    }
+
+    /// The file name of this part, if it is a file upload.
+    #[must_use]
+    pub fn file_name(&self) -> Option<&str> {
+        self.field.file_name()
+    }
+
+    /// The content type of this part, if one was sent.
+    #[must_use]
+    pub fn content_type(&self) -> Option<Mime> {
+        self.field.content_type()?.as_ref().parse().ok()
+    }
+
+    /// Read the next chunk of this part, or `None` if it has been fully read.
+    async fn chunk(&mut self) -> crate::Result<Option<Bytes>> {
+        if !self.buf.is_empty() {
+            return Ok(Some(std::mem::take(&mut self.buf)));
+        }
+        self.field.chunk().await.map_err(multipart_error)
+    }
+
+    /// Read the rest of this part into a byte buffer.
+    pub async fn bytes(mut self) -> crate::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    /// Read the rest of this part into a string.
+    ///
+    /// # Errors
+    ///
+    /// A `400 Bad Request` error is returned if the part is not valid UTF-8.
+    pub async fn text(self) -> crate::Result<String> {
+        String::from_utf8(self.bytes().await?)
+            .map_err(|err| crate::Error::new(StatusCode::BadRequest, err))
+    }
+}
+
+impl Debug for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Part")
+            .field("name", &self.name())
+            .field("file_name", &self.file_name())
+            .field("content_type", &self.content_type())
+            .finish()
+    }
+}
+
+impl Read for Part {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let available = futures_util::ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl BufRead for Part {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        while this.buf.is_empty() {
+            match futures_util::ready!(Pin::new(&mut this.field).poll_next(cx)) {
+                Some(Ok(chunk)) => this.buf = chunk,
+                Some(Err(err)) => return Poll::Ready(Err(io_error(err))),
+                None => break,
+            }
+        }
+        Poll::Ready(Ok(&this.buf))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        let rest = self.buf.slice(amt..);
+        self.buf = rest;
+    }
+}
+
+/// Convert a parse error into an I/O error, passing on errors reading the
+/// body as they are so [`body_error`] still recognizes exceeded limits.
+fn io_error(err: multer::Error) -> io::Error {
+    match err {
+        multer::Error::StreamReadFailed(err) => match err.downcast::<io::Error>() {
+            Ok(err) => *err,
+            Err(err) => io::Error::new(io::ErrorKind::InvalidData, err),
+        },
+        err => io::Error::new(io::ErrorKind::InvalidData, err),
+    }
+}
+
+/// Convert a parse error into an error with a fitting status code.
+fn multipart_error(err: multer::Error) -> crate::Error {
+    match err {
+        multer::Error::StreamReadFailed(err) => match err.downcast::<io::Error>() {
+            Ok(err) => body_error(crate::Error::new(StatusCode::BadRequest, *err)),
+            Err(err) => crate::Error::from_str(StatusCode::BadRequest, err.to_string()),
+        },
+        multer::Error::NoMultipart => crate::Error::from_str(
+            StatusCode::UnsupportedMediaType,
+            "Expected request with `Content-Type: multipart/form-data`",
+        ),
+        err @ multer::Error::FieldSizeExceeded { .. }
+        | err @ multer::Error::StreamSizeExceeded { .. } => {
+            crate::Error::new(StatusCode::PayloadTooLarge, err)
+        }
+        err => crate::Error::new(StatusCode::BadRequest, err),
+    }
+}
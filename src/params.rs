@@ -0,0 +1,283 @@
+//! Deserialization of route parameters.
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, Deserializer, IntoDeserializer, Visitor};
+
+use std::fmt::{self, Display};
+
+/// The error returned when route parameters fail to deserialize.
+#[derive(Debug)]
+pub(crate) struct ParamsError(String);
+
+impl Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+impl de::Error for ParamsError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Deserializes a list of `(name, value)` route parameters.
+///
+/// Structs and maps are deserialized by name, sequences and tuples by
+/// position, and any other type from the single parameter present.
+pub(crate) struct ParamsDeserializer<'de> {
+    params: Vec<(&'de str, &'de str)>,
+}
+
+impl<'de> ParamsDeserializer<'de> {
+    /// Create a new deserializer. Later parameters with the same name take
+    /// precedence over earlier ones, but keep their original position.
+    pub(crate) fn new(params: impl IntoIterator<Item = (&'de str, &'de str)>) -> Self {
+        let mut deduped: Vec<(&str, &str)> = Vec::new();
+        for (name, value) in params {
+            match deduped.iter_mut().find(|(existing, _)| *existing == name) {
+                Some(param) => param.1 = value,
+                None => deduped.push((name, value)),
+            }
+        }
+        Self { params: deduped }
+    }
+
+    fn single(self) -> Result<ValueDeserializer<'de>, ParamsError> {
+        match self.params.as_slice() {
+            [(name, value)] => Ok(ValueDeserializer { name, value }),
+            params => Err(ParamsError(format!(
+                "expected a single route parameter, found {}",
+                params.len()
+            ))),
+        }
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ParamsDeserializer<'de> {
+    type Error = ParamsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let params = self
+            .params
+            .into_iter()
+            .map(|(name, value)| (name, ValueDeserializer { name, value }));
+        let mut map = MapDeserializer::new(params);
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let values = self
+            .params
+            .into_iter()
+            .map(|(name, value)| ValueDeserializer { name, value });
+        let mut seq = SeqDeserializer::new(values);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_unit_struct(name, visitor)
+    }
+
+    forward_to_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_option
+        deserialize_unit deserialize_identifier deserialize_ignored_any
+    }
+}
+
+/// Deserializes the value of a single named route parameter.
+struct ValueDeserializer<'de> {
+    name: &'de str,
+    value: &'de str,
+}
+
+impl<'de> ValueDeserializer<'de> {
+    fn parse<T>(&self) -> Result<T, ParamsError>
+    where
+        T: std::str::FromStr,
+        T::Err: Display,
+    {
+        self.value.parse().map_err(|err| {
+            ParamsError(format!(
+                "failed to parse route parameter `{}`: {}",
+                self.name, err
+            ))
+        })
+    }
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = ParamsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.value)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ParamsError> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[test]
+    fn later_params_take_precedence() {
+        let params = vec![("id", "1"), ("name", "nori"), ("id", "2")];
+        let map = HashMap::<String, String>::deserialize(ParamsDeserializer::new(params)).unwrap();
+        assert_eq!(map["id"], "2");
+        assert_eq!(map["name"], "nori");
+    }
+
+    #[test]
+    fn parse_errors_name_the_param() {
+        #[derive(Debug, Deserialize)]
+        struct Params {
+            #[allow(dead_code)]
+            id: u32,
+        }
+
+        let err = Params::deserialize(ParamsDeserializer::new(vec![("id", "seven")])).unwrap_err();
+        assert!(err.to_string().contains("`id`"), "{}", err);
+    }
+
+    #[test]
+    fn single_value_requires_one_param() {
+        let err =
+            u32::deserialize(ParamsDeserializer::new(vec![("a", "1"), ("b", "2")])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected a single route parameter, found 2"
+        );
+    }
+}
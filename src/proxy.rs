@@ -0,0 +1,283 @@
+//! Reverse proxying to upstream servers.
+//!
+//! A [`Proxy`] forwards the requests of a route to one or more upstream
+//! servers, and streams their responses back to the client.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use async_std::task::block_on;
+//! # fn main() -> Result<(), std::io::Error> { block_on(async {
+//! #
+//! use std::time::Duration;
+//! use tide::proxy::Proxy;
+//!
+//! let mut app = tide::new();
+//! app.at("/legacy/*").proxy(
+//!     Proxy::new("http://10.0.0.1:8080/app/")
+//!         .upstream("http://10.0.0.2:8080/app/")
+//!         .timeout(Duration::from_secs(10)),
+//! );
+//! app.listen("127.0.0.1:8080").await?;
+//! #
+//! # Ok(()) }) }
+//! ```
+
+use crate::http::headers::{HeaderName, Headers, CONNECTION, FORWARDED, HOST};
+use crate::http::proxies::Forwarded;
+use crate::http::url::Position;
+use crate::http::{self, Url};
+use crate::security::Forwarded as Resolved;
+use crate::{Endpoint, Request, Response, Result, StatusCode};
+
+use async_std::future;
+use http_client::h1::H1Client;
+use http_client::HttpClient;
+use kv_log_macro::{info, warn};
+
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Headers that tell upstream servers about the client.
+const FORWARDING: [&str; 4] = [
+    "Forwarded",
+    "X-Forwarded-For",
+    "X-Forwarded-Host",
+    "X-Forwarded-Proto",
+];
+
+/// Headers that only apply to a single connection, and so are never
+/// forwarded.
+const HOP_BY_HOP: [&str; 9] = [
+    "Connection",
+    "Keep-Alive",
+    "Proxy-Authenticate",
+    "Proxy-Authorization",
+    "Proxy-Connection",
+    "TE",
+    "Trailer",
+    "Transfer-Encoding",
+    "Upgrade",
+];
+
+/// An endpoint that forwards requests to upstream servers.
+///
+/// Mount it with [`Route::proxy`](crate::Route::proxy). The part of the path
+/// matched by the route's wildcard is appended to the path of the upstream
+/// URL: with `Proxy::new("http://upstream/app/")` mounted at
+/// `/legacy/*`, a request for `/legacy/users?page=2` is forwarded to
+/// `http://upstream/app/users?page=2`.
+///
+/// Requests and responses are streamed in both directions. Hop-by-hop
+/// headers such as `Connection` and `Transfer-Encoding` are removed, and the
+/// `Forwarded`, `X-Forwarded-For`, `X-Forwarded-Host` and
+/// `X-Forwarded-Proto` headers tell the upstream server about the client, as
+/// given by [`Request::remote`] and [`Request::host`]. When the client was
+/// found by the [`TrustedProxies`](crate::security::TrustedProxies)
+/// middleware, the forwarding headers it read are replaced rather than
+/// extended.
+///
+/// Upstream errors are answered with `502 Bad Gateway`, and upstream
+/// responses that take longer than the [`timeout`](Proxy::timeout) with
+/// `504 Gateway Timeout`.
+#[derive(Debug, Clone)]
+pub struct Proxy {
+    upstreams: Vec<Url>,
+    next: Arc<AtomicUsize>,
+    client: Arc<dyn HttpClient>,
+    timeout: Option<Duration>,
+}
+
+impl Proxy {
+    /// Create a new instance of `Proxy` forwarding requests to `upstream`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upstream` is not a valid URL.
+    pub fn new<U>(upstream: U) -> Self
+    where
+        U: TryInto<Url>,
+        U::Error: Debug,
+    {
+        Self {
+            upstreams: vec![parse_url(upstream)],
+            next: Arc::new(AtomicUsize::new(0)),
+            client: Arc::new(H1Client::new()),
+            timeout: None,
+        }
+    }
+
+    /// Add another upstream to forward requests to.
+    ///
+    /// Requests are spread across upstreams in round-robin order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upstream` is not a valid URL.
+    #[must_use]
+    pub fn upstream<U>(mut self, upstream: U) -> Self
+    where
+        U: TryInto<Url>,
+        U::Error: Debug,
+    {
+        self.upstreams.push(parse_url(upstream));
+        self
+    }
+
+    /// Set how long to wait for an upstream to respond, after which the
+    /// request is answered with `504 Gateway Timeout`.
+    ///
+    /// The timeout covers sending the request and receiving the response
+    /// headers, not streaming the response body. Defaults to no timeout
+    /// other than the client's own.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the HTTP client used to send requests upstream.
+    ///
+    /// Defaults to an HTTP/1.1 client with connection pooling.
+    #[must_use]
+    pub fn client(mut self, client: impl HttpClient) -> Self {
+        self.client = Arc::new(client);
+        self
+    }
+
+    /// The URL to forward a request for `url` to, whose route's wildcard
+    /// matched `rest`.
+    fn upstream_url(&self, url: &Url, rest: &str) -> Url {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.upstreams.len();
+        let mut upstream = self.upstreams[index].clone();
+        let path = format!(
+            "{}/{}",
+            upstream.path().trim_end_matches('/'),
+            rest.trim_start_matches('/')
+        );
+        upstream.set_path(&path);
+        upstream.set_query(url.query());
+        upstream
+    }
+}
+
+#[async_trait::async_trait]
+impl<State> Endpoint<State> for Proxy
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: Request<State>) -> Result {
+        let rest = req.wildcard().unwrap_or_default().to_owned();
+        let resolved = req.ext::<Resolved>();
+        let host = match resolved.and_then(|resolved| resolved.host.as_deref()) {
+            Some(host) => host.to_owned(),
+            None => match req.header(HOST) {
+                Some(host) => host.as_str().to_owned(),
+                None => req.url()[Position::BeforeHost..Position::AfterPort].to_owned(),
+            },
+        };
+        let resolved = resolved.is_some();
+        let proto = req.url().scheme().to_owned();
+        let client_ip = req.remote().map(|addr| match addr.parse::<SocketAddr>() {
+            Ok(addr) => addr.ip().to_string(),
+            Err(_) => addr.to_owned(),
+        });
+
+        let mut req: http::Request = req.into();
+        let url = self.upstream_url(req.url(), &rest);
+        info!("Proxying request", {
+            method: req.method().to_string(),
+            path: req.url().path(),
+            upstream: url.as_str(),
+        });
+
+        remove_hop_by_hop(&mut req);
+        req.remove_header(HOST);
+        if resolved {
+            for name in FORWARDING {
+                req.remove_header(name);
+            }
+        }
+
+        let mut forwarded = Forwarded::new();
+        forwarded.add_for(match &client_ip {
+            Some(ip) if ip.contains(':') => format!("[{}]", ip),
+            Some(ip) => ip.clone(),
+            None => "unknown".to_owned(),
+        });
+        forwarded.set_host(host.as_str());
+        forwarded.set_proto(proto.as_str());
+        let forwarded = forwarded.value()?;
+        append(&mut req, FORWARDED, &forwarded);
+        if let Some(client_ip) = &client_ip {
+            append(&mut req, "X-Forwarded-For".into(), client_ip);
+        }
+        req.insert_header("X-Forwarded-Host", host);
+        req.insert_header("X-Forwarded-Proto", proto);
+        *req.url_mut() = url;
+
+        let sent = self.client.send(req);
+        let sent = match self.timeout {
+            Some(timeout) => match future::timeout(timeout, sent).await {
+                Ok(sent) => sent,
+                Err(_) => {
+                    warn!("Upstream timed out");
+                    return Ok(Response::new(StatusCode::GatewayTimeout));
+                }
+            },
+            None => sent.await,
+        };
+        let mut res = match sent {
+            Ok(res) => res,
+            Err(e) => {
+                warn!("Upstream failed", { error: e.to_string() });
+                return Ok(Response::new(StatusCode::BadGateway));
+            }
+        };
+        remove_hop_by_hop(&mut res);
+        Ok(res.into())
+    }
+}
+
+/// Remove hop-by-hop headers, including the ones listed in `Connection`.
+fn remove_hop_by_hop(mut headers: impl AsMut<Headers>) {
+    let headers = headers.as_mut();
+    let listed: Vec<String> = headers
+        .get(CONNECTION)
+        .into_iter()
+        .flat_map(|values| values.iter())
+        .flat_map(|value| value.as_str().split(','))
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .collect();
+    for name in listed {
+        headers.remove(name.as_str());
+    }
+    for name in HOP_BY_HOP.iter() {
+        headers.remove(*name);
+    }
+}
+
+/// Append `value` to the comma-separated list in the header `name`.
+fn append(headers: &mut http::Request, name: HeaderName, value: &str) {
+    let list = match headers.header(&name) {
+        Some(values) => {
+            let values: Vec<_> = values.iter().map(|value| value.as_str()).collect();
+            format!("{}, {}", values.join(", "), value)
+        }
+        None => value.to_owned(),
+    };
+    headers.insert_header(name, list);
+}
+
+fn parse_url<U>(url: U) -> Url
+where
+    U: TryInto<Url>,
+    U::Error: Debug,
+{
+    url.try_into().expect("Could not convert into a valid url")
+}
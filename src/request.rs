@@ -12,6 +12,8 @@ use crate::http::cookies::Cookie;
 use crate::http::format_err;
 use crate::http::headers::{self, HeaderName, HeaderValues, ToHeaderValues};
 use crate::http::{self, Body, Method, Mime, StatusCode, Url, Version};
+use crate::params::ParamsDeserializer;
+use crate::security::{body_error, Forwarded};
 use crate::Response;
 
 pin_project_lite::pin_project! {
@@ -133,25 +135,30 @@ impl<State> Request<State> {
 
     /// Get the remote address for this request.
     ///
-    /// This is determined in the following priority:
-    /// 1. `Forwarded` header `for` key
-    /// 2. The first `X-Forwarded-For` header
-    /// 3. Peer address of the transport
+    /// This is the address of the client as found by the
+    /// [`TrustedProxies`](crate::security::TrustedProxies) middleware, or the
+    /// peer address of the transport. Forwarding headers are ignored unless
+    /// they were set by a trusted proxy.
     #[must_use]
     pub fn remote(&self) -> Option<&str> {
-        self.req.remote()
+        self.ext::<Forwarded>()
+            .and_then(|forwarded| forwarded.remote.as_deref())
+            .or_else(|| self.peer_addr())
     }
 
     /// Get the destination host for this request.
     ///
     /// This is determined in the following priority:
-    /// 1. `Forwarded` header `host` key
-    /// 2. The first `X-Forwarded-Host` header
-    /// 3. `Host` header
-    /// 4. URL domain, if any
+    /// 1. The host found by the
+    ///    [`TrustedProxies`](crate::security::TrustedProxies) middleware
+    /// 2. `Host` header
+    /// 3. URL domain, if any
     #[must_use]
     pub fn host(&self) -> Option<&str> {
-        self.req.host()
+        self.ext::<Forwarded>()
+            .and_then(|forwarded| forwarded.host.as_deref())
+            .or_else(|| self.header(headers::HOST).map(|host| host.as_str()))
+            .or_else(|| self.url().host_str())
     }
 
     /// Get the request content type as a `Mime`.
@@ -304,6 +311,99 @@ impl<State> Request<State> {
             .ok_or_else(|| format_err!("Param \"{}\" not found", key.to_string()))
     }
 
+    /// Extract a route parameter by name and parse it into `T`.
+    ///
+    /// The name should *not* include the leading `:`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `key` is not a valid parameter for the route.
+    /// If the parameter fails to parse, a `400 Bad Request` error naming the
+    /// parameter is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use async_std::task::block_on;
+    /// # fn main() -> Result<(), std::io::Error> { block_on(async {
+    /// #
+    /// use tide::{Request, Result};
+    ///
+    /// async fn get_cat(req: Request<()>) -> Result<String> {
+    ///     let id: u64 = req.param_as("id")?;
+    ///     Ok(format!("Cat #{}", id))
+    /// }
+    ///
+    /// let mut app = tide::new();
+    /// app.at("/cats/:id").get(get_cat);
+    /// app.listen("127.0.0.1:8080").await?;
+    /// #
+    /// # Ok(()) })}
+    /// ```
+    pub fn param_as<T>(&self, key: &str) -> crate::Result<T>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.param(key)?.parse().map_err(|err| {
+            http::Error::from_str(
+                StatusCode::BadRequest,
+                format!("failed to parse route parameter `{}`: {}", key, err),
+            )
+        })
+    }
+
+    /// Deserialize all route parameters into `T`.
+    ///
+    /// Parameters captured by nested servers are included; if several
+    /// routers capture a parameter with the same name, the innermost one
+    /// wins, as with [`Request::param`].
+    ///
+    /// `T` can be a struct or map, in which case parameters are matched by
+    /// name; a tuple, in which case they are matched by position; or any
+    /// other type if the route has exactly one parameter.
+    ///
+    /// # Errors
+    ///
+    /// A `400 Bad Request` error naming the offending parameter is returned
+    /// if deserialization fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use async_std::task::block_on;
+    /// # fn main() -> Result<(), std::io::Error> { block_on(async {
+    /// #
+    /// use tide::http::convert::Deserialize;
+    /// use tide::{Request, Result};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Params {
+    ///     user: String,
+    ///     id: u64,
+    /// }
+    ///
+    /// async fn get_cat(req: Request<()>) -> Result<String> {
+    ///     let Params { user, id } = req.params()?;
+    ///     Ok(format!("{}'s cat #{}", user, id))
+    /// }
+    ///
+    /// let mut app = tide::new();
+    /// app.at("/users/:user/cats/:id").get(get_cat);
+    /// app.listen("127.0.0.1:8080").await?;
+    /// #
+    /// # Ok(()) })}
+    /// ```
+    pub fn params<'de, T: serde::de::Deserialize<'de>>(&'de self) -> crate::Result<T> {
+        let params = self
+            .route_params
+            .iter()
+            .flat_map(|captures| captures.params())
+            .map(|capture| (capture.name(), capture.value()));
+        T::deserialize(ParamsDeserializer::new(params))
+            .map_err(|err| http::Error::new(StatusCode::BadRequest, err))
+    }
+
     /// Fetch the wildcard from the route, if it exists
     ///
     /// Returns the parameter as a `&str`, borrowed from this `Request`.
@@ -416,7 +516,7 @@ impl<State> Request<State> {
     /// # Ok(()) })}
     /// ```
     pub async fn body_bytes(&mut self) -> crate::Result<Vec<u8>> {
-        let res = self.req.body_bytes().await?;
+        let res = self.req.body_bytes().await.map_err(body_error)?;
         Ok(res)
     }
 
@@ -450,7 +550,7 @@ impl<State> Request<State> {
     /// # Ok(()) })}
     /// ```
     pub async fn body_string(&mut self) -> crate::Result<String> {
-        let res = self.req.body_string().await?;
+        let res = self.req.body_string().await.map_err(body_error)?;
         Ok(res)
     }
 
@@ -464,7 +564,7 @@ impl<State> Request<State> {
     /// If the body cannot be interpreted as valid json for the target type `T`,
     /// an `Err` is returned.
     pub async fn body_json<T: serde::de::DeserializeOwned>(&mut self) -> crate::Result<T> {
-        let res = self.req.body_json().await?;
+        let res = self.req.body_json().await.map_err(body_error)?;
         Ok(res)
     }
 
@@ -501,10 +601,29 @@ impl<State> Request<State> {
     /// # Ok(()) })}
     /// ```
     pub async fn body_form<T: serde::de::DeserializeOwned>(&mut self) -> crate::Result<T> {
-        let res = self.req.body_form().await?;
+        let res = self.req.body_form().await.map_err(body_error)?;
         Ok(res)
     }
 
+    /// Parse the body as `multipart/form-data`.
+    ///
+    /// This returns a [`Multipart`](crate::multipart::Multipart) yielding the
+    /// parts of the body as they are received. See the
+    /// [`multipart`](crate::multipart) module for details.
+    ///
+    /// # Errors
+    ///
+    /// A `415 Unsupported Media Type` error is returned if the request is not
+    /// `multipart/form-data`, and a `400 Bad Request` error if its
+    /// `Content-Type` has no boundary.
+    #[cfg(feature = "multipart")]
+    pub fn body_multipart(&mut self) -> crate::Result<crate::multipart::Multipart> {
+        let content_type = self
+            .header(headers::CONTENT_TYPE)
+            .map(|values| values.last().as_str().to_owned());
+        crate::multipart::Multipart::new(self.take_body(), content_type.as_deref())
+    }
+
     /// returns a `Cookie` by name of the cookie.
     #[cfg(feature = "cookies")]
     #[must_use]
@@ -1,9 +1,11 @@
 use serde::Serialize;
 
+use crate::http::conditional::{ETag, LastModified};
 use crate::http::headers::{HeaderName, ToHeaderValues};
 use crate::http::{Body, Mime, StatusCode};
 use crate::Response;
 use std::convert::TryInto;
+use std::time::SystemTime;
 
 #[derive(Debug)]
 
@@ -68,6 +70,41 @@ impl ResponseBuilder {
         self
     }
 
+    /// Sets the `ETag` header on the response.
+    ///
+    /// [`ConditionalGet`](crate::conditional::ConditionalGet) uses it instead
+    /// of computing an `ETag` from the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tide::{http::conditional::ETag, Response};
+    /// let response = Response::builder(200).etag(ETag::new_weak("v1".into())).build();
+    /// assert_eq!(response["etag"], "W/\"v1\"");
+    /// ```
+    pub fn etag(mut self, etag: ETag) -> Self {
+        etag.apply(&mut self.0);
+        self
+    }
+
+    /// Sets the `Last-Modified` header on the response.
+    ///
+    /// [`ConditionalGet`](crate::conditional::ConditionalGet) uses it instead
+    /// of computing an `ETag` from the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::SystemTime;
+    /// # use tide::Response;
+    /// let response = Response::builder(200).last_modified(SystemTime::UNIX_EPOCH).build();
+    /// assert_eq!(response["last-modified"], "Thu, 01 Jan 1970 00:00:00 GMT");
+    /// ```
+    pub fn last_modified(mut self, time: SystemTime) -> Self {
+        LastModified::new(time).apply(&mut self.0);
+        self
+    }
+
     /// Sets the body of the response.
     ///
     /// # Examples
@@ -5,6 +5,8 @@ use std::sync::Arc;
 
 use crate::endpoint::MiddlewareEndpoint;
 use crate::fs::{ServeDir, ServeFile};
+#[cfg(feature = "proxy")]
+use crate::proxy::Proxy;
 use crate::{router::Router, Endpoint, Middleware};
 
 use kv_log_macro::trace;
@@ -27,6 +29,8 @@ pub struct Route<'a, State> {
     ///
     /// [`strip_prefix`]: #method.strip_prefix
     prefix: bool,
+    /// The limit set by [`body_limit`](#method.body_limit), if any.
+    body_limit: Option<usize>,
 }
 
 impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
@@ -36,6 +40,7 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
             path,
             middleware: Vec::new(),
             prefix: false,
+            body_limit: None,
         }
     }
 
@@ -56,6 +61,7 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
             path: p,
             middleware: self.middleware.clone(),
             prefix: false,
+            body_limit: self.body_limit,
         }
     }
 
@@ -91,9 +97,19 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
         self
     }
 
+    /// Limit the size of request bodies for the current route.
+    ///
+    /// This overrides any limit set by a [`BodyLimit`](crate::security::BodyLimit)
+    /// applied to the server.
+    pub fn body_limit(&mut self, limit: usize) -> &mut Self {
+        self.body_limit = Some(limit);
+        self.with(crate::security::BodyLimit::new(limit))
+    }
+
     /// Reset the middleware chain for the current route, if any.
     pub fn reset_middleware(&mut self) -> &mut Self {
         self.middleware.clear();
+        self.body_limit = None;
         self
     }
 
@@ -142,7 +158,10 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
     /// Serve a directory statically.
     ///
     /// Each file will be streamed from disk, and a mime type will be determined
-    /// based on magic bytes.
+    /// based on magic bytes. Conditional requests and `Range` requests are
+    /// supported, using the file's size and modification time as validators.
+    /// Directories are served by their `index.html`; use
+    /// [`serve_dir_with`](Route::serve_dir_with) to configure this.
     ///
     /// # Security
     ///
@@ -165,22 +184,102 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
     /// }
     /// ```
     pub fn serve_dir(&mut self, dir: impl AsRef<Path>) -> io::Result<()> {
-        // Verify path exists, return error if it doesn't.
-        let dir = dir.as_ref().to_owned().canonicalize()?;
-        let prefix = self.path().to_string();
-        self.get(ServeDir::new(prefix, dir));
+        self.serve_dir_with(ServeDir::new(dir)?);
+        Ok(())
+    }
+
+    /// Serve a directory statically, as configured by `serve_dir`.
+    ///
+    /// See [`ServeDir`] for the available options, such as index files and
+    /// directory listings.
+    ///
+    /// # Examples
+    ///
+    /// Serve `./public/docs/*` from `localhost:8080/docs/*`, listing
+    /// directories without an `index.html`.
+    ///
+    /// ```no_run
+    /// #[async_std::main]
+    /// async fn main() -> Result<(), std::io::Error> {
+    ///     let mut app = tide::new();
+    ///     app.at("/docs/*")
+    ///         .serve_dir_with(tide::fs::ServeDir::new("public/docs/")?.autoindex(true));
+    ///     app.listen("127.0.0.1:8080").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn serve_dir_with(&mut self, mut serve_dir: ServeDir) -> &mut Self {
+        serve_dir.set_prefix(self.path().to_string());
+        self.get(serve_dir)
+    }
+
+    /// Serve a single-page application from a directory.
+    ///
+    /// Files in `dir` are served like with [`serve_dir`](Route::serve_dir),
+    /// but paths that do not exist get `fallback`, a file relative to `dir`,
+    /// instead of `404 Not Found`. Paths that look like asset requests still
+    /// get a `404`; see [`ServeDir::fallback`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[async_std::main]
+    /// async fn main() -> Result<(), std::io::Error> {
+    ///     let mut app = tide::new();
+    ///     app.at("/app/*").serve_spa("dist/", "index.html")?;
+    ///     app.listen("127.0.0.1:8080").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn serve_spa(
+        &mut self,
+        dir: impl AsRef<Path>,
+        fallback: impl AsRef<Path>,
+    ) -> io::Result<()> {
+        self.serve_dir_with(ServeDir::new(dir)?.fallback(fallback));
         Ok(())
     }
 
     /// Serve a static file.
     ///
     /// The file will be streamed from disk, and a mime type will be determined
-    /// based on magic bytes. Similar to serve_dir
+    /// based on magic bytes. Similar to serve_dir, conditional requests and
+    /// `Range` requests are supported.
     pub fn serve_file(&mut self, file: impl AsRef<Path>) -> io::Result<()> {
-        self.get(ServeFile::init(file)?);
+        self.get(ServeFile::new(file)?);
         Ok(())
     }
 
+    /// Serve a static file, as configured by `serve_file`.
+    ///
+    /// See [`ServeFile`] for the available options.
+    pub fn serve_file_with(&mut self, serve_file: ServeFile) -> &mut Self {
+        self.get(serve_file)
+    }
+
+    /// Forward requests to upstream servers.
+    ///
+    /// The part of the path matched by the route's wildcard is appended to
+    /// the upstream URL, and requests with any method are accepted. See [`Proxy`] for the
+    /// available options.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[async_std::main]
+    /// async fn main() -> Result<(), std::io::Error> {
+    ///     let mut app = tide::new();
+    ///     app.at("/legacy/*")
+    ///         .proxy(tide::proxy::Proxy::new("http://localhost:8081/"));
+    ///     app.listen("127.0.0.1:8080").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "proxy")]
+    pub fn proxy(&mut self, proxy: Proxy) -> &mut Self {
+        self.all(proxy)
+    }
+
     /// Add an endpoint for the given HTTP method
     pub fn method(&mut self, method: http_types::Method, ep: impl Endpoint<State>) -> &mut Self {
         if self.prefix {
@@ -190,12 +289,14 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
                 &wildcard.path,
                 method,
                 MiddlewareEndpoint::wrap_with_middleware(ep, &wildcard.middleware),
+                wildcard.body_limit,
             );
         } else {
             self.router.add(
                 &self.path,
                 method,
                 MiddlewareEndpoint::wrap_with_middleware(ep, &self.middleware),
+                self.body_limit,
             );
         }
         self
@@ -211,11 +312,13 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
             wildcard.router.add_all(
                 &wildcard.path,
                 MiddlewareEndpoint::wrap_with_middleware(ep, &wildcard.middleware),
+                wildcard.body_limit,
             );
         } else {
             self.router.add_all(
                 &self.path,
                 MiddlewareEndpoint::wrap_with_middleware(ep, &self.middleware),
+                self.body_limit,
             );
         }
         self
@@ -252,6 +355,9 @@ impl<'a, State: Clone + Send + Sync + 'static> Route<'a, State> {
     }
 
     /// Add an endpoint for `OPTIONS` requests
+    ///
+    /// Without one, `OPTIONS` requests to a path with other endpoints are
+    /// answered with `204 No Content` and an `Allow` header listing them.
     pub fn options(&mut self, ep: impl Endpoint<State>) -> &mut Self {
         self.method(http_types::Method::Options, ep);
         self
@@ -2,7 +2,10 @@ use routefinder::{Captures, Router as MethodRouter};
 use std::collections::HashMap;
 
 use crate::endpoint::DynEndpoint;
-use crate::{Request, Response, StatusCode};
+use crate::http::headers::ALLOW;
+use crate::http::server::Allow;
+use crate::http::Method;
+use crate::{Endpoint, Request, Response, StatusCode};
 
 /// The routing table used by `Server`
 ///
@@ -10,8 +13,10 @@ use crate::{Request, Response, StatusCode};
 /// by the method first allows the table itself to be more efficient.
 #[allow(missing_debug_implementations)]
 pub(crate) struct Router<State> {
-    method_map: HashMap<http_types::Method, MethodRouter<Box<DynEndpoint<State>>>>,
-    all_method_router: MethodRouter<Box<DynEndpoint<State>>>,
+    method_map: HashMap<http_types::Method, MethodRouter<RouteEndpoint<State>>>,
+    all_method_router: MethodRouter<RouteEndpoint<State>>,
+    not_found: Option<Box<DynEndpoint<State>>>,
+    method_not_allowed: Option<Box<DynEndpoint<State>>>,
 }
 
 impl<State> std::fmt::Debug for Router<State> {
@@ -19,6 +24,23 @@ impl<State> std::fmt::Debug for Router<State> {
         f.debug_struct("Router")
             .field("method_map", &self.method_map)
             .field("all_method_router", &self.all_method_router)
+            .field("not_found", &self.not_found.is_some())
+            .field("method_not_allowed", &self.method_not_allowed.is_some())
+            .finish()
+    }
+}
+
+/// An endpoint in the routing table
+pub(crate) struct RouteEndpoint<State> {
+    endpoint: Box<DynEndpoint<State>>,
+    /// The limit set with [`Route::body_limit`](crate::Route::body_limit).
+    body_limit: Option<usize>,
+}
+
+impl<State> std::fmt::Debug for RouteEndpoint<State> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteEndpoint")
+            .field("body_limit", &self.body_limit)
             .finish()
     }
 }
@@ -27,6 +49,11 @@ impl<State> std::fmt::Debug for Router<State> {
 pub(crate) struct Selection<'a, State> {
     pub(crate) endpoint: &'a DynEndpoint<State>,
     pub(crate) params: Captures<'static, 'static>,
+    /// The methods the path can be requested with, if the request's method
+    /// did not match.
+    pub(crate) allow: Option<Allow>,
+    /// The body limit of the route, which overrides server-wide limits.
+    pub(crate) body_limit: Option<usize>,
 }
 
 impl<State: Clone + Send + Sync + 'static> Router<State> {
@@ -34,6 +61,8 @@ impl<State: Clone + Send + Sync + 'static> Router<State> {
         Router {
             method_map: HashMap::default(),
             all_method_router: MethodRouter::new(),
+            not_found: None,
+            method_not_allowed: None,
         }
     }
 
@@ -42,7 +71,12 @@ impl<State: Clone + Send + Sync + 'static> Router<State> {
         path: &str,
         method: http_types::Method,
         ep: Box<DynEndpoint<State>>,
+        body_limit: Option<usize>,
     ) {
+        let ep = RouteEndpoint {
+            endpoint: ep,
+            body_limit,
+        };
         self.method_map
             .entry(method)
             .or_default()
@@ -50,10 +84,27 @@ impl<State: Clone + Send + Sync + 'static> Router<State> {
             .unwrap()
     }
 
-    pub(crate) fn add_all(&mut self, path: &str, ep: Box<DynEndpoint<State>>) {
+    pub(crate) fn add_all(
+        &mut self,
+        path: &str,
+        ep: Box<DynEndpoint<State>>,
+        body_limit: Option<usize>,
+    ) {
+        let ep = RouteEndpoint {
+            endpoint: ep,
+            body_limit,
+        };
         self.all_method_router.add(path, ep).unwrap()
     }
 
+    pub(crate) fn set_not_found(&mut self, ep: Box<DynEndpoint<State>>) {
+        self.not_found = Some(ep);
+    }
+
+    pub(crate) fn set_method_not_allowed(&mut self, ep: Box<DynEndpoint<State>>) {
+        self.method_not_allowed = Some(Box::new(AllowEndpoint(ep)));
+    }
+
     pub(crate) fn route(&self, path: &str, method: http_types::Method) -> Selection<'_, State> {
         if let Some(m) = self
             .method_map
@@ -61,38 +112,72 @@ impl<State: Clone + Send + Sync + 'static> Router<State> {
             .and_then(|r| r.best_match(path))
         {
             Selection {
-                endpoint: m.handler(),
+                endpoint: &*m.handler().endpoint,
                 params: m.captures().into_owned(),
+                allow: None,
+                body_limit: m.handler().body_limit,
             }
         } else if let Some(m) = self.all_method_router.best_match(path) {
             Selection {
-                endpoint: m.handler(),
+                endpoint: &*m.handler().endpoint,
                 params: m.captures().into_owned(),
+                allow: None,
+                body_limit: m.handler().body_limit,
             }
         } else if method == http_types::Method::Head {
             // If it is a HTTP HEAD request then check if there is a callback in the endpoints map
             // if not then fallback to the behavior of HTTP GET else proceed as usual
 
             self.route(path, http_types::Method::Get)
-        } else if self
-            .method_map
-            .iter()
-            .filter(|(k, _)| **k != method)
-            .any(|(_, r)| r.best_match(path).is_some())
-        {
+        } else if let Some(allow) = self.allowed_methods(path, method) {
             // If this `path` can be handled by a callback registered with a different HTTP method
-            // should return 405 Method Not Allowed
+            // answer OPTIONS requests with the allowed methods, and anything else with
+            // 405 Method Not Allowed
+            let endpoint = match (&self.method_not_allowed, method) {
+                (_, Method::Options) => &options_endpoint,
+                (Some(ep), _) => &**ep,
+                (None, _) => &method_not_allowed,
+            };
             Selection {
-                endpoint: &method_not_allowed,
+                endpoint,
                 params: Captures::default(),
+                allow: Some(allow),
+                body_limit: None,
             }
         } else {
+            let endpoint = match &self.not_found {
+                Some(ep) => &**ep,
+                None => &not_found_endpoint,
+            };
             Selection {
-                endpoint: &not_found_endpoint,
+                endpoint,
                 params: Captures::default(),
+                allow: None,
+                body_limit: None,
             }
         }
     }
+
+    /// Collect the methods `path` has endpoints registered for, other than
+    /// `method`. Returns `None` if there are none.
+    fn allowed_methods(&self, path: &str, method: Method) -> Option<Allow> {
+        let mut methods = self
+            .method_map
+            .iter()
+            .filter(|(k, _)| **k != method)
+            .filter(|(_, r)| r.best_match(path).is_some())
+            .map(|(k, _)| *k)
+            .peekable();
+        methods.peek()?;
+
+        let mut allow = Allow::new();
+        methods.for_each(|m| allow.insert(m));
+        if allow.contains(Method::Get) {
+            allow.insert(Method::Head);
+        }
+        allow.insert(Method::Options);
+        Some(allow)
+    }
 }
 
 async fn not_found_endpoint<State: Clone + Send + Sync + 'static>(
@@ -102,7 +187,37 @@ async fn not_found_endpoint<State: Clone + Send + Sync + 'static>(
 }
 
 async fn method_not_allowed<State: Clone + Send + Sync + 'static>(
-    _req: Request<State>,
+    req: Request<State>,
+) -> crate::Result {
+    let mut res = Response::new(StatusCode::MethodNotAllowed);
+    if let Some(allow) = req.ext::<Allow>() {
+        allow.apply(&mut res);
+    }
+    Ok(res)
+}
+
+async fn options_endpoint<State: Clone + Send + Sync + 'static>(
+    req: Request<State>,
 ) -> crate::Result {
-    Ok(Response::new(StatusCode::MethodNotAllowed))
+    let mut res = Response::new(StatusCode::NoContent);
+    if let Some(allow) = req.ext::<Allow>() {
+        allow.apply(&mut res);
+    }
+    Ok(res)
+}
+
+/// Wraps a custom 405 endpoint so its responses carry an `Allow` header,
+/// unless the endpoint set one itself.
+struct AllowEndpoint<State>(Box<DynEndpoint<State>>);
+
+#[async_trait::async_trait]
+impl<State: Clone + Send + Sync + 'static> Endpoint<State> for AllowEndpoint<State> {
+    async fn call(&self, req: Request<State>) -> crate::Result {
+        let allow = req.ext::<Allow>().map(Allow::value);
+        let mut res = self.0.call(req).await?;
+        if let (Some(allow), None) = (allow, res.header(ALLOW)) {
+            res.insert_header(ALLOW, allow);
+        }
+        Ok(res)
+    }
 }
@@ -0,0 +1,211 @@
+use async_std::io::{self, prelude::*};
+use async_std::task::{Context, Poll};
+use http_types::{Body, StatusCode};
+
+use std::fmt::{self, Display};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::middleware::{Middleware, Next};
+use crate::{Request, Result};
+
+/// Middleware that caps the size of request bodies.
+///
+/// Requests whose `Content-Length` exceeds the limit are answered with
+/// `413 Payload Too Large` before they reach the endpoint. For requests
+/// without a `Content-Length`, the limit is enforced while the body is
+/// streamed and reading past it fails, in which case
+/// [`Request::body_bytes`], [`Request::body_string`],
+/// [`Request::body_json`] and [`Request::body_form`] return a
+/// `413 Payload Too Large` error.
+///
+/// When applied more than once, for example server-wide and again on a
+/// single route, the innermost limit wins, so routes can both raise and lower
+/// the server-wide limit. See also [`Route::body_limit`](crate::Route::body_limit).
+///
+/// # Example
+///
+/// ```no_run
+/// use tide::security::BodyLimit;
+///
+/// let mut app = tide::new();
+/// app.with(BodyLimit::new(64 * 1024));
+/// app.at("/upload")
+///     .body_limit(16 * 1024 * 1024)
+///     .put(|_| async { Ok("uploaded") });
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BodyLimit {
+    limit: usize,
+}
+
+impl BodyLimit {
+    /// Creates a new `BodyLimit` allowing bodies of up to `limit` bytes.
+    #[must_use]
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// The maximum body size in bytes.
+    #[must_use]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// The limit set on the route a request was routed to, which takes precedence
+/// over server-wide limits.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RouteBodyLimit(pub(crate) usize);
+
+/// The limit shared between all `BodyLimit`s a request passes through.
+#[derive(Clone)]
+struct SharedLimit(Arc<AtomicUsize>);
+
+impl SharedLimit {
+    /// Fail if `read` bytes exceed the current limit.
+    fn check(&self, read: usize) -> io::Result<()> {
+        let limit = self.0.load(Ordering::SeqCst);
+        if read > limit {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                LimitExceeded(limit),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for BodyLimit {
+    async fn handle(&self, mut req: Request<State>, next: Next<'_, State>) -> Result {
+        match req.ext::<SharedLimit>() {
+            Some(shared) => shared.0.store(self.limit, Ordering::SeqCst),
+            None => {
+                let shared = SharedLimit(Arc::new(AtomicUsize::new(self.limit)));
+                let body = req.take_body();
+                let len = body.len();
+                let mime = body.mime().clone();
+                let mut body = Body::from_reader(
+                    LimitedBody {
+                        body,
+                        len,
+                        read: 0,
+                        limit: shared.clone(),
+                    },
+                    len,
+                );
+                body.set_mime(mime);
+                req.set_body(body);
+                req.set_ext(shared);
+            }
+        }
+
+        // A server-wide limit is checked against the limit of the route, which
+        // is applied later on and overrides it.
+        let limit = match req.ext::<RouteBodyLimit>() {
+            Some(route) => route.0,
+            None => self.limit,
+        };
+        if req.len().is_some_and(|len| len > limit) {
+            return Err(crate::Error::new(
+                StatusCode::PayloadTooLarge,
+                LimitExceeded(limit),
+            ));
+        }
+
+        // Endpoints reading the body as a stream fail with the I/O error of
+        // the exceeded limit, which is answered with a `413` as well.
+        let mut res = next.run(req).await;
+        if res.error().is_some_and(is_exceeded) {
+            res.set_status(StatusCode::PayloadTooLarge);
+        }
+        Ok(res)
+    }
+}
+
+/// The error a limited body fails with once it exceeds its limit.
+#[derive(Debug)]
+struct LimitExceeded(usize);
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Request body exceeds the limit of {} bytes", self.0)
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+/// Give errors caused by a body exceeding its [`BodyLimit`] a `413` status.
+pub(crate) fn body_error(mut err: crate::Error) -> crate::Error {
+    if is_exceeded(&err) {
+        err.set_status(StatusCode::PayloadTooLarge);
+    }
+    err
+}
+
+/// Whether `err` was caused by a body exceeding its [`BodyLimit`], looking
+/// through I/O errors wrapping it, such as those of `io::copy`.
+fn is_exceeded(err: &crate::Error) -> bool {
+    let mut source = err
+        .downcast_ref::<io::Error>()
+        .map(|err| err as &(dyn std::error::Error + 'static));
+    while let Some(err) = source {
+        if err.is::<LimitExceeded>() {
+            return true;
+        }
+        source = match err.downcast_ref::<io::Error>() {
+            Some(err) => err.get_ref().map(|err| err as _),
+            None => err.source(),
+        };
+    }
+    false
+}
+
+struct LimitedBody {
+    body: Body,
+    len: Option<usize>,
+    read: usize,
+    limit: SharedLimit,
+}
+
+impl LimitedBody {
+    /// Fail early if the declared length already exceeds the limit.
+    fn check_len(&self) -> io::Result<()> {
+        match self.len {
+            Some(len) => self.limit.check(len),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Read for LimitedBody {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.check_len()?;
+        let n = futures_util::ready!(Pin::new(&mut self.body).poll_read(cx, buf))?;
+        self.read = self.read.saturating_add(n);
+        self.limit.check(self.read)?;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl BufRead for LimitedBody {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        this.check_len()?;
+        let buf = futures_util::ready!(Pin::new(&mut this.body).poll_fill_buf(cx))?;
+        this.limit.check(this.read.saturating_add(buf.len()))?;
+        Poll::Ready(Ok(buf))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut self.body).consume(amt);
+        self.read = self.read.saturating_add(amt);
+    }
+}
@@ -1,5 +1,12 @@
-//! HTTP Security Headers.
+//! HTTP security middleware.
 
+mod body_limit;
 mod cors;
+mod trusted_proxies;
 
+pub use body_limit::BodyLimit;
+pub(crate) use body_limit::{body_error, RouteBodyLimit};
 pub use cors::{CorsMiddleware, Origin};
+pub use trusted_proxies::TrustedProxies;
+
+pub(crate) use trusted_proxies::Forwarded;
@@ -0,0 +1,277 @@
+use crate::http::headers::{HeaderName, Headers, FORWARDED};
+use crate::http::Url;
+use crate::middleware::{Middleware, Next};
+use crate::{Request, Result};
+
+use std::net::{IpAddr, SocketAddr};
+
+/// Middleware that honours forwarding headers set by trusted proxies.
+///
+/// Without it, [`Request::remote`] is the peer address of the connection
+/// and [`Request::host`] comes from the `Host` header, since forwarding
+/// headers can be set by any client. With it, requests whose peer address is
+/// in one of the trusted ranges have their `Forwarded` header, or their
+/// `X-Forwarded-For`, `X-Forwarded-Host` and `X-Forwarded-Proto` headers,
+/// read to find the client's address, and the host and scheme it used:
+///
+/// - [`Request::remote`] is the address of the client, found by walking the
+///   chain of forwarding proxies from the nearest one and skipping trusted
+///   addresses.
+/// - [`Request::host`] and the host of [`Request::url`] are the host the
+///   client asked for.
+/// - The scheme of [`Request::url`] is the scheme the client used.
+///
+/// # Examples
+///
+/// ```
+/// use tide::security::TrustedProxies;
+///
+/// let mut app = tide::new();
+/// app.with(TrustedProxies::new().trust("10.0.0.0/8").trust("::1"));
+/// app.at("/").get(|req: tide::Request<()>| async move {
+///     Ok(format!("Hello {}", req.remote().unwrap_or("stranger")))
+/// });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    ranges: Vec<IpRange>,
+}
+
+impl TrustedProxies {
+    /// Create a new instance of `TrustedProxies` that trusts no proxy.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust proxies with an address in `range`.
+    ///
+    /// Ranges are written in CIDR notation, such as `10.0.0.0/8` or
+    /// `fd00::/8`. A single address, such as `127.0.0.1`, trusts only that
+    /// address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is not a valid IP address or CIDR range.
+    #[must_use]
+    pub fn trust(mut self, range: &str) -> Self {
+        let parsed = IpRange::parse(range);
+        self.ranges
+            .push(parsed.unwrap_or_else(|| panic!("Invalid IP range: {:?}", range)));
+        self
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            ip => ip,
+        };
+        self.ranges.iter().any(|range| range.contains(ip))
+    }
+
+    /// Find the client of a request forwarded by the trusted peer of the
+    /// connection.
+    fn client(&self, headers: &Headers) -> Option<Forwarded> {
+        if let Some(values) = headers.get(FORWARDED) {
+            let elements: Vec<_> = values
+                .iter()
+                .flat_map(|value| value.as_str().split(','))
+                .map(parse_element)
+                .collect();
+            let nodes: Vec<_> = elements
+                .iter()
+                .map(|element| element.remote.as_deref())
+                .collect();
+            let index = self.client_index(&nodes);
+            return index.map(|index| elements[index].clone());
+        }
+
+        let forwarded_for = list(headers, "X-Forwarded-For");
+        let nodes: Vec<_> = forwarded_for.iter().map(|node| Some(*node)).collect();
+        let index = self.client_index(&nodes)?;
+        Some(Forwarded {
+            remote: Some(forwarded_for[index].to_owned()),
+            host: list(headers, "X-Forwarded-Host")
+                .pop()
+                .map(ToOwned::to_owned),
+            proto: list(headers, "X-Forwarded-Proto")
+                .pop()
+                .map(ToOwned::to_owned),
+        })
+    }
+
+    /// The index of the client in a chain of forwarded `nodes`, which is the
+    /// nearest untrusted one, or the farthest if all are trusted.
+    fn client_index(&self, nodes: &[Option<&str>]) -> Option<usize> {
+        let mut client = None;
+        for (index, node) in nodes.iter().enumerate().rev() {
+            client = Some(index);
+            match node.and_then(parse_node) {
+                Some(ip) if self.is_trusted(ip) => continue,
+                _ => break,
+            }
+        }
+        client
+    }
+}
+
+#[async_trait::async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for TrustedProxies {
+    async fn handle(&self, mut req: Request<State>, next: Next<'_, State>) -> Result {
+        let peer = req.peer_addr().and_then(parse_node);
+        let forwarded = match peer {
+            Some(peer) if self.is_trusted(peer) => self.client(req.req.as_ref()),
+            _ => None,
+        };
+        if let Some(mut forwarded) = forwarded {
+            forwarded.remote = forwarded
+                .remote
+                .map(|node| parse_node(&node).map_or(node, |ip| ip.to_string()));
+            let url = req.req.url_mut();
+            if let Some(proto) = &forwarded.proto {
+                if proto.eq_ignore_ascii_case("http") || proto.eq_ignore_ascii_case("https") {
+                    url.set_scheme(&proto.to_ascii_lowercase()).ok();
+                }
+            }
+            if let Some(host) = &forwarded.host {
+                if let Ok(parsed) = Url::parse(&format!("{}://{}", url.scheme(), host)) {
+                    url.set_host(parsed.host_str()).ok();
+                    url.set_port(parsed.port()).ok();
+                }
+            }
+            req.set_ext(forwarded);
+        }
+        Ok(next.run(req).await)
+    }
+}
+
+/// What trusted proxies say about the client of a request.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Forwarded {
+    pub(crate) remote: Option<String>,
+    pub(crate) host: Option<String>,
+    proto: Option<String>,
+}
+
+/// Parse an element of a `Forwarded` header, such as
+/// `for=192.0.2.60;proto=http;by=203.0.113.43`.
+fn parse_element(element: &str) -> Forwarded {
+    let mut forwarded = Forwarded::default();
+    for pair in element.split(';') {
+        let (key, value) = match pair.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim().trim_matches('"')),
+            None => continue,
+        };
+        let field = if key.eq_ignore_ascii_case("for") {
+            &mut forwarded.remote
+        } else if key.eq_ignore_ascii_case("host") {
+            &mut forwarded.host
+        } else if key.eq_ignore_ascii_case("proto") {
+            &mut forwarded.proto
+        } else {
+            continue;
+        };
+        *field = Some(value.to_owned());
+    }
+    forwarded
+}
+
+/// The comma-separated values of the header `name`.
+fn list<'a>(headers: &'a Headers, name: &str) -> Vec<&'a str> {
+    headers
+        .get(HeaderName::from(name))
+        .into_iter()
+        .flat_map(|values| values.iter())
+        .flat_map(|value| value.as_str().split(','))
+        .map(str::trim)
+        .filter(|node| !node.is_empty())
+        .collect()
+}
+
+/// Parse the IP address of a node, which may have a port and, for IPv6,
+/// brackets.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    node.parse::<IpAddr>()
+        .or_else(|_| node.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .ok()
+        .or_else(|| {
+            let ip = node.strip_prefix('[')?.split(']').next()?;
+            ip.parse().ok()
+        })
+}
+
+/// A range of IP addresses in CIDR notation.
+#[derive(Debug, Clone, Copy)]
+struct IpRange {
+    network: IpAddr,
+    prefix: u32,
+}
+
+impl IpRange {
+    fn parse(range: &str) -> Option<Self> {
+        let (network, prefix) = match range.split_once('/') {
+            Some((network, prefix)) => (network.parse().ok()?, Some(prefix.parse().ok()?)),
+            None => (range.parse().ok()?, None),
+        };
+        let max = match network {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix = prefix.unwrap_or(max);
+        if prefix > max {
+            return None;
+        }
+        Some(Self { network, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ip_ranges() {
+        let contains =
+            |range, ip: &str| IpRange::parse(range).unwrap().contains(ip.parse().unwrap());
+        assert!(contains("10.0.0.0/8", "10.1.2.3"));
+        assert!(!contains("10.0.0.0/8", "11.0.0.1"));
+        assert!(contains("0.0.0.0/0", "192.0.2.1"));
+        assert!(contains("127.0.0.1", "127.0.0.1"));
+        assert!(!contains("127.0.0.1", "127.0.0.2"));
+        assert!(contains("fd00::/8", "fd12::1"));
+        assert!(!contains("fd00::/8", "10.0.0.1"));
+        assert!(IpRange::parse("10.0.0.0/33").is_none());
+        assert!(IpRange::parse("localhost").is_none());
+    }
+
+    #[test]
+    fn nodes() {
+        let ip = |node| parse_node(node).map(|ip| ip.to_string());
+        assert_eq!(ip("192.0.2.43"), Some("192.0.2.43".to_owned()));
+        assert_eq!(ip("192.0.2.43:47011"), Some("192.0.2.43".to_owned()));
+        assert_eq!(
+            ip("\"[2001:db8:cafe::17]:4711\""),
+            Some("2001:db8:cafe::17".to_owned())
+        );
+        assert_eq!(
+            ip("[2001:db8:cafe::17]"),
+            Some("2001:db8:cafe::17".to_owned())
+        );
+        assert_eq!(ip("unknown"), None);
+    }
+}
@@ -6,10 +6,19 @@ use kv_log_macro::{info, trace};
 
 #[cfg(feature = "cookies")]
 use crate::cookies;
-use crate::listener::{Listener, ToListener};
+#[cfg(feature = "h1-server")]
+use crate::http::headers::CONNECTION;
+#[cfg(feature = "h1-server")]
+use crate::listener::{is_closed, ConnectionInfo, Limited};
+use crate::listener::{Listener, Shutdown, ToListener};
 use crate::middleware::{Middleware, Next};
 use crate::router::{Router, Selection};
+use crate::security::RouteBodyLimit;
 use crate::{Endpoint, Request, Route};
+#[cfg(feature = "h1-server")]
+use async_h1::server::ConnectionStatus;
+#[cfg(feature = "h1-server")]
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// An HTTP server.
 ///
@@ -166,6 +175,55 @@ where
         Route::new(router, path.to_owned())
     }
 
+    /// Set the endpoint used when no route matches the request path.
+    ///
+    /// By default an empty `404 Not Found` response is returned. The endpoint
+    /// receives the full request and runs inside the server's middleware
+    /// stack, like any other endpoint. A nested server's fallback applies to
+    /// paths below the route it is nested at.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use async_std::task::block_on;
+    /// # fn main() -> Result<(), std::io::Error> { block_on(async {
+    /// #
+    /// use tide::{Response, StatusCode};
+    ///
+    /// let mut app = tide::new();
+    /// app.at("/").get(|_| async { Ok("Hello, world!") });
+    /// app.not_found(|req: tide::Request<()>| async move {
+    ///     Ok(Response::builder(StatusCode::NotFound)
+    ///         .body(format!("nothing to see at {}", req.url().path())))
+    /// });
+    /// app.listen("127.0.0.1:8080").await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub fn not_found(&mut self, endpoint: impl Endpoint<State>) -> &mut Self {
+        let router = Arc::get_mut(&mut self.router)
+            .expect("Registering routes is not possible after the Server has started");
+        router.set_not_found(Box::new(endpoint));
+        self
+    }
+
+    /// Set the endpoint used when a route matches the request path, but not
+    /// its method.
+    ///
+    /// By default an empty `405 Method Not Allowed` response is returned. The
+    /// endpoint receives the full request and runs inside the server's
+    /// middleware stack, like any other endpoint.
+    ///
+    /// The methods the path does support are available to the endpoint as an
+    /// [`Allow`](crate::http::server::Allow) request extension, and are sent
+    /// in an `Allow` header unless the endpoint sets one itself.
+    pub fn method_not_allowed(&mut self, endpoint: impl Endpoint<State>) -> &mut Self {
+        let router = Arc::get_mut(&mut self.router)
+            .expect("Registering routes is not possible after the Server has started");
+        router.set_method_not_allowed(Box::new(endpoint));
+        self
+    }
+
     /// Add middleware to an application.
     ///
     /// Middleware provides customization of the request/response cycle, such as compression,
@@ -213,6 +271,52 @@ where
         Ok(())
     }
 
+    /// Asynchronously serve the app with the supplied listener until the
+    /// [`Shutdown`] handle is triggered.
+    ///
+    /// Once shutdown is triggered the listener stops accepting new
+    /// connections, and this method returns after all in-flight connections
+    /// have finished or the drain timeout of the `Shutdown` has elapsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use async_std::task::block_on;
+    /// # fn main() -> Result<(), std::io::Error> { block_on(async {
+    /// #
+    /// use tide::listener::Shutdown;
+    ///
+    /// let mut app = tide::new();
+    /// let shutdown = Shutdown::new();
+    /// let handle = shutdown.clone();
+    /// app.at("/").get(|_| async { Ok("Hello, world!") });
+    /// app.at("/shutdown").post(move |_| {
+    ///     let handle = handle.clone();
+    ///     async move {
+    ///         handle.trigger();
+    ///         Ok("Goodbye!")
+    ///     }
+    /// });
+    /// app.listen_with_shutdown("127.0.0.1:8080", shutdown).await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub async fn listen_with_shutdown<L: ToListener<State>>(
+        self,
+        listener: L,
+        shutdown: Shutdown,
+    ) -> io::Result<()> {
+        let mut listener = listener.to_listener()?;
+        listener.set_shutdown(shutdown);
+        listener.bind(self).await?;
+        for info in listener.info().iter() {
+            info!("Server listening on {}", info);
+        }
+        listener.accept().await?;
+        info!("Server shut down");
+        Ok(())
+    }
+
     /// Asynchronously bind the listener.
     ///
     /// Bind the listener. This starts the listening process by opening the
@@ -251,6 +355,111 @@ where
         Ok(listener)
     }
 
+    /// Serve HTTP/1 requests from a single connection until it closes.
+    ///
+    /// This runs the same connection loop as the built-in listeners on any
+    /// duplex byte stream, such as an in-memory pipe or a custom transport,
+    /// and is the building block for custom
+    /// [`Listener`](crate::listener::Listener)s. `info` sets the addresses
+    /// requests appear to come from and be received on, and whether the
+    /// connection is encrypted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use async_std::io::prelude::*;
+    /// use async_std::task;
+    /// use tide::listener::ConnectionInfo;
+    ///
+    /// let mut app = tide::new();
+    /// app.at("/").get(|req: tide::Request<()>| async move {
+    ///     Ok(format!("Hello, {}!", req.peer_addr().unwrap_or("stranger")))
+    /// });
+    ///
+    /// let (mut client, stream) = tide::testing::duplex();
+    /// let info = ConnectionInfo::new().peer_addr("pipe:1");
+    /// task::spawn(async move { app.serve_connection(stream, info).await });
+    ///
+    /// client
+    ///     .write_all(b"GET / HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n")
+    ///     .await?;
+    /// let mut res = String::new();
+    /// client.read_to_string(&mut res).await?;
+    /// assert!(res.ends_with("Hello, pipe:1!"));
+    /// # std::io::Result::Ok(()) }).unwrap();
+    /// ```
+    #[cfg(feature = "h1-server")]
+    pub async fn serve_connection<RW>(
+        &self,
+        stream: RW,
+        info: ConnectionInfo,
+    ) -> http_types::Result<()>
+    where
+        RW: io::Read + io::Write + Send + Sync + Unpin + 'static,
+    {
+        let stream = async_dup::Arc::new(async_dup::Mutex::new(stream));
+        match info.shutdown.clone() {
+            Some(shutdown) => shutdown
+                .track(self.serve_h1(stream, info))
+                .await
+                .unwrap_or(Ok(())),
+            None => self.serve_h1(stream, info).await,
+        }
+    }
+
+    /// Serve HTTP/1 requests from a connection that can be cloned, without
+    /// wrapping it in a lock.
+    ///
+    /// The connection is not tracked by its [`Shutdown`], which listeners
+    /// do before spawning it.
+    #[cfg(feature = "h1-server")]
+    pub(crate) async fn serve_h1<RW>(
+        &self,
+        stream: RW,
+        info: ConnectionInfo,
+    ) -> http_types::Result<()>
+    where
+        RW: io::Read + io::Write + Clone + Send + Sync + Unpin + 'static,
+    {
+        let ConnectionInfo {
+            peer_addr,
+            local_addr,
+            tls,
+            shutdown,
+            options,
+        } = info;
+        let stream = Limited::new(stream, &options, shutdown.as_ref());
+        let requests = AtomicUsize::new(0);
+
+        let mut server = async_h1::server::Server::new(stream.clone(), |mut req| async {
+            if tls {
+                req.url_mut().set_scheme("https").ok();
+            }
+            req.set_local_addr(local_addr.as_ref());
+            req.set_peer_addr(peer_addr.as_ref());
+            let requests = requests.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut res: http_types::Response = self.respond(req).await?;
+            if options.closes_after(requests)
+                || shutdown.as_ref().is_some_and(Shutdown::is_triggered)
+            {
+                res.insert_header(CONNECTION, "close");
+            }
+            Ok(res)
+        });
+        let mut first = true;
+        loop {
+            stream.start_head(first);
+            first = false;
+            match server.accept_one().await {
+                Ok(ConnectionStatus::KeepAlive) => continue,
+                Ok(ConnectionStatus::Close) => return Ok(()),
+                Err(error) if is_closed(&error) => return Ok(()),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
     /// Respond to a `Request` with a `Response`.
     ///
     /// This method is useful for testing endpoints directly,
@@ -287,9 +496,20 @@ where
         } = self.clone();
 
         let method = req.method().to_owned();
-        let Selection { endpoint, params } = router.route(req.url().path(), method);
+        let Selection {
+            endpoint,
+            params,
+            allow,
+            body_limit,
+        } = router.route(req.url().path(), method);
         let route_params = vec![params];
-        let req = Request::new(state, req, route_params);
+        let mut req = Request::new(state, req, route_params);
+        if let Some(allow) = allow {
+            req.set_ext(allow);
+        }
+        if let Some(limit) = body_limit {
+            req.set_ext(RouteBodyLimit(limit));
+        }
 
         let next = Next {
             endpoint,
@@ -349,9 +569,20 @@ impl<State: Clone + Sync + Send + 'static, InnerState: Clone + Sync + Send + 'st
         let middleware = self.middleware.clone();
         let state = self.state.clone();
 
-        let Selection { endpoint, params } = router.route(&path, method);
+        let Selection {
+            endpoint,
+            params,
+            allow,
+            body_limit,
+        } = router.route(&path, method);
         route_params.push(params);
-        let req = Request::new(state, req, route_params);
+        let mut req = Request::new(state, req, route_params);
+        if let Some(allow) = allow {
+            req.set_ext(allow);
+        }
+        if let Some(limit) = body_limit {
+            req.set_ext(RouteBodyLimit(limit));
+        }
 
         let next = Next {
             endpoint,
@@ -362,6 +593,29 @@ impl<State: Clone + Sync + Send + 'static, InnerState: Clone + Sync + Send + 'st
     }
 }
 
+/// A `Server` is an [`HttpClient`](http_client::HttpClient) that sends
+/// requests to itself with [`Server::respond`], without any sockets.
+///
+/// Request and response bodies are streamed, not buffered. To have requests
+/// appear to come from a peer address, or to persist cookies, wrap the server
+/// in a [`TestClient`](crate::testing::TestClient).
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// use std::convert::TryInto;
+///
+/// let mut app = tide::new();
+/// app.at("/").get(|_| async { Ok("hello world") });
+///
+/// let client: surf::Client = surf::Config::new()
+///     .set_http_client(app)
+///     .set_base_url(tide::http::Url::parse("http://example.com")?)
+///     .try_into()?;
+/// assert_eq!(client.get("/").recv_string().await?, "hello world");
+/// # tide::Result::Ok(()) }).unwrap();
+/// ```
 #[crate::utils::async_trait]
 impl<State: Clone + Send + Sync + Unpin + 'static> http_client::HttpClient for Server<State> {
     async fn send(&self, req: crate::http::Request) -> crate::http::Result<crate::http::Response> {
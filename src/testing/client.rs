@@ -0,0 +1,395 @@
+use crate::http::headers::{HeaderName, ToHeaderValues};
+use crate::http::{self, Body, Method, Mime, Url};
+#[cfg(feature = "h1-server")]
+use crate::listener::ConnectionInfo;
+use crate::testing::TestResponse;
+use crate::Server;
+
+use futures_util::future::BoxFuture;
+use http_client::HttpClient;
+#[cfg(feature = "h1-server")]
+use kv_log_macro::error;
+use serde::Serialize;
+
+use std::convert::TryInto;
+use std::fmt::{self, Debug};
+use std::future::IntoFuture;
+#[cfg(feature = "cookies")]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "cookies")]
+use crate::http::cookies::{Cookie, CookieJar};
+#[cfg(feature = "cookies")]
+use crate::http::headers::{COOKIE, SET_COOKIE};
+
+/// A client that sends requests to a [`Server`] in-process.
+///
+/// Requests are built with a method such as [`get`](TestClient::get) or
+/// [`post`](TestClient::post), and sent by awaiting them. Paths are joined to
+/// the [base URL](TestClient::base_url), which defaults to
+/// `http://example.com/`.
+///
+/// By default requests are handed straight to [`Server::respond`]. With
+/// [`h1`](TestClient::h1) they are instead encoded by the async-h1 client,
+/// sent over an in-memory [`duplex`](super::duplex) stream, and decoded by
+/// the async-h1 server, just as they would be over a real connection.
+///
+/// With the `cookies` feature, cookies set by responses are stored and sent
+/// with later requests, regardless of their domain and path. Clones of a
+/// client share its cookies.
+///
+/// `TestClient` implements [`HttpClient`], so it can also be handed to code
+/// that sends requests through one, such as a surf `Client`.
+pub struct TestClient<State> {
+    server: Server<State>,
+    base_url: Url,
+    h1: bool,
+    peer_addr: Option<String>,
+    local_addr: Option<String>,
+    #[cfg(feature = "cookies")]
+    cookies: Arc<Mutex<CookieJar>>,
+}
+
+impl<State> TestClient<State>
+where
+    State: Clone + Send + Sync + 'static,
+{
+    /// Create a new instance of `TestClient` sending requests to `server`.
+    pub fn new(server: Server<State>) -> Self {
+        Self {
+            server,
+            base_url: Url::parse("http://example.com/").unwrap(),
+            h1: false,
+            peer_addr: None,
+            local_addr: None,
+            #[cfg(feature = "cookies")]
+            cookies: Arc::new(Mutex::new(CookieJar::new())),
+        }
+    }
+
+    /// Set the URL that request paths are joined to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base_url` is not a valid URL.
+    #[must_use]
+    pub fn base_url<U>(mut self, base_url: U) -> Self
+    where
+        U: TryInto<Url>,
+        U::Error: Debug,
+    {
+        self.base_url = base_url
+            .try_into()
+            .expect("Could not convert into a valid url");
+        self
+    }
+
+    /// Send requests through the async-h1 codec over an in-memory stream,
+    /// rather than straight to the server.
+    #[cfg(feature = "h1-server")]
+    #[must_use]
+    pub fn h1(mut self, h1: bool) -> Self {
+        self.h1 = h1;
+        self
+    }
+
+    /// Set the address requests appear to come from, as returned by
+    /// [`Request::peer_addr`](crate::Request::peer_addr).
+    ///
+    /// Requests that already have a peer address keep it.
+    #[must_use]
+    pub fn peer_addr(mut self, addr: impl ToString) -> Self {
+        self.peer_addr = Some(addr.to_string());
+        self
+    }
+
+    /// Set the address requests appear to be received on, as returned by
+    /// [`Request::local_addr`](crate::Request::local_addr).
+    ///
+    /// Requests that already have a local address keep it.
+    #[must_use]
+    pub fn local_addr(mut self, addr: impl ToString) -> Self {
+        self.local_addr = Some(addr.to_string());
+        self
+    }
+
+    /// Get the cookie `name` stored by this client.
+    #[cfg(feature = "cookies")]
+    pub fn cookie(&self, name: &str) -> Option<Cookie<'static>> {
+        self.cookies.lock().unwrap().get(name).cloned()
+    }
+
+    /// Build a request with `method` for `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` cannot be joined to the base URL.
+    pub fn request(&self, method: Method, path: &str) -> TestRequest<State> {
+        let url = self
+            .base_url
+            .join(path)
+            .expect("Could not join path to the base url");
+        TestRequest {
+            client: self.clone(),
+            req: http::Request::new(method, url),
+        }
+    }
+
+    /// Builds a `CONNECT` request.
+    pub fn connect(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Connect, path)
+    }
+
+    /// Builds a `DELETE` request.
+    pub fn delete(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Delete, path)
+    }
+
+    /// Builds a `GET` request.
+    pub fn get(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Get, path)
+    }
+
+    /// Builds a `HEAD` request.
+    pub fn head(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Head, path)
+    }
+
+    /// Builds an `OPTIONS` request.
+    pub fn options(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Options, path)
+    }
+
+    /// Builds a `PATCH` request.
+    pub fn patch(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Patch, path)
+    }
+
+    /// Builds a `POST` request.
+    pub fn post(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Post, path)
+    }
+
+    /// Builds a `PUT` request.
+    pub fn put(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Put, path)
+    }
+
+    /// Builds a `TRACE` request.
+    pub fn trace(&self, path: &str) -> TestRequest<State> {
+        self.request(Method::Trace, path)
+    }
+
+    /// Send a request to the server.
+    pub async fn send(&self, req: impl Into<http::Request>) -> crate::Result<TestResponse> {
+        let mut req = req.into();
+        if req.peer_addr().is_none() {
+            req.set_peer_addr(self.peer_addr.as_ref());
+        }
+        if req.local_addr().is_none() {
+            req.set_local_addr(self.local_addr.as_ref());
+        }
+        #[cfg(feature = "cookies")]
+        self.add_cookies(&mut req);
+
+        #[cfg(feature = "h1-server")]
+        let res = if self.h1 {
+            self.send_h1(req).await?
+        } else {
+            self.server.respond(req).await?
+        };
+        #[cfg(not(feature = "h1-server"))]
+        let res: http::Response = self.server.respond(req).await?;
+
+        #[cfg(feature = "cookies")]
+        self.store_cookies(&res);
+        Ok(TestResponse::new(res))
+    }
+
+    #[cfg(feature = "h1-server")]
+    async fn send_h1(&self, req: http::Request) -> crate::Result<http::Response> {
+        let (client, stream) = super::duplex();
+        let server = self.server.clone();
+        let mut info = ConnectionInfo::new().tls(self.base_url.scheme() == "https");
+        if let Some(peer_addr) = req.peer_addr() {
+            info = info.peer_addr(peer_addr);
+        }
+        if let Some(local_addr) = req.local_addr() {
+            info = info.local_addr(local_addr);
+        }
+        async_std::task::spawn(async move {
+            if let Err(error) = server.serve_h1(stream, info).await {
+                error!("async-h1 error", { error: error.to_string() });
+            }
+        });
+        async_h1::connect(client, req).await
+    }
+
+    #[cfg(feature = "cookies")]
+    fn add_cookies(&self, req: &mut http::Request) {
+        let jar = self.cookies.lock().unwrap();
+        let cookies: Vec<String> = jar
+            .iter()
+            .map(|cookie| {
+                Cookie::new(cookie.name().to_owned(), cookie.value().to_owned())
+                    .encoded()
+                    .to_string()
+            })
+            .collect();
+        if !cookies.is_empty() {
+            req.insert_header(COOKIE, cookies.join("; "));
+        }
+    }
+
+    #[cfg(feature = "cookies")]
+    fn store_cookies(&self, res: &http::Response) {
+        let mut jar = self.cookies.lock().unwrap();
+        let values = res.header(SET_COOKIE).into_iter();
+        for value in values.flat_map(|values| values.iter()) {
+            let cookie = match Cookie::parse_encoded(value.as_str().to_owned()) {
+                Ok(cookie) => cookie,
+                Err(_) => continue,
+            };
+            if matches!(cookie.max_age(), Some(age) if age.is_zero()) {
+                jar.remove(Cookie::named(cookie.name().to_owned()));
+            } else {
+                jar.add(cookie);
+            }
+        }
+    }
+}
+
+impl<State: Clone> Clone for TestClient<State> {
+    fn clone(&self) -> Self {
+        Self {
+            server: self.server.clone(),
+            base_url: self.base_url.clone(),
+            h1: self.h1,
+            peer_addr: self.peer_addr.clone(),
+            local_addr: self.local_addr.clone(),
+            #[cfg(feature = "cookies")]
+            cookies: self.cookies.clone(),
+        }
+    }
+}
+
+impl<State> Debug for TestClient<State> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestClient")
+            .field("base_url", &self.base_url.as_str())
+            .field("h1", &self.h1)
+            .field("peer_addr", &self.peer_addr)
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<State> HttpClient for TestClient<State>
+where
+    State: Clone + Send + Sync + Unpin + 'static,
+{
+    async fn send(&self, req: http::Request) -> http::Result<http::Response> {
+        let res = TestClient::send(self, req).await?;
+        Ok(res.into())
+    }
+}
+
+/// A request built by a [`TestClient`], sent by awaiting it.
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// use tide::testing::TestClient;
+///
+/// let mut app = tide::new();
+/// app.at("/echo").post(|mut req: tide::Request<()>| async move {
+///     Ok(req.body_string().await?)
+/// });
+///
+/// let client = TestClient::new(app);
+/// let mut res = client.post("/echo").body("hello").await?;
+/// res.assert_status(200);
+/// res.assert_body("hello").await;
+/// # tide::Result::Ok(()) }).unwrap();
+/// ```
+#[must_use = "requests are only sent when awaited"]
+pub struct TestRequest<State> {
+    client: TestClient<State>,
+    req: http::Request,
+}
+
+impl<State> TestRequest<State> {
+    /// Set a header, replacing any previous values.
+    pub fn header(mut self, name: impl Into<HeaderName>, values: impl ToHeaderValues) -> Self {
+        self.req.insert_header(name, values);
+        self
+    }
+
+    /// Set the `Content-Type` of the request.
+    pub fn content_type(mut self, mime: impl Into<Mime>) -> Self {
+        self.req.set_content_type(mime.into());
+        self
+    }
+
+    /// Set the query string of the request from `query`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `query` cannot be encoded as a query string.
+    pub fn query(mut self, query: &impl Serialize) -> Self {
+        self.req
+            .set_query(query)
+            .expect("Could not encode the query string");
+        self
+    }
+
+    /// Set the body of the request.
+    pub fn body(mut self, body: impl Into<Body>) -> Self {
+        self.req.set_body(body);
+        self
+    }
+
+    /// Set the body of the request to `json`, with a JSON content type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `json` cannot be serialized.
+    pub fn body_json(self, json: &impl Serialize) -> Self {
+        let body = Body::from_json(json).expect("Could not serialize the body");
+        self.body(body)
+    }
+
+    /// Set the body of the request to `form`, as a URL-encoded form.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `form` cannot be serialized.
+    pub fn body_form(self, form: &impl Serialize) -> Self {
+        let body = Body::from_form(form).expect("Could not serialize the body");
+        self.body(body)
+    }
+}
+
+impl<State> IntoFuture for TestRequest<State>
+where
+    State: Clone + Send + Sync + 'static,
+{
+    type Output = crate::Result<TestResponse>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Self { client, req } = self;
+        Box::pin(async move { client.send(req).await })
+    }
+}
+
+impl<State> Debug for TestRequest<State> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestRequest")
+            .field("method", &self.req.method())
+            .field("url", &self.req.url().as_str())
+            .finish()
+    }
+}
@@ -0,0 +1,133 @@
+use async_std::io::{self, Read, Write};
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Create a pair of connected in-memory byte streams.
+///
+/// Bytes written to one stream can be read from the other. Once a stream and
+/// all of its clones are dropped, or it is closed, reads from its peer return
+/// end of file when the buffered bytes run out.
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// use async_std::io::prelude::*;
+///
+/// let (mut client, mut server) = tide::testing::duplex();
+/// client.write_all(b"ping").await?;
+/// drop(client);
+///
+/// let mut received = String::new();
+/// server.read_to_string(&mut received).await?;
+/// assert_eq!(received, "ping");
+/// # std::io::Result::Ok(()) }).unwrap();
+/// ```
+pub fn duplex() -> (DuplexStream, DuplexStream) {
+    let a = Arc::new(Mutex::new(Pipe::default()));
+    let b = Arc::new(Mutex::new(Pipe::default()));
+    let first = Ends {
+        read: a.clone(),
+        write: b.clone(),
+    };
+    let second = Ends { read: b, write: a };
+    (
+        DuplexStream {
+            ends: Arc::new(first),
+        },
+        DuplexStream {
+            ends: Arc::new(second),
+        },
+    )
+}
+
+/// One end of an in-memory byte stream, created with [`duplex`].
+///
+/// Clones share the same end of the stream.
+#[derive(Debug, Clone)]
+pub struct DuplexStream {
+    ends: Arc<Ends>,
+}
+
+#[derive(Debug)]
+struct Ends {
+    read: Arc<Mutex<Pipe>>,
+    write: Arc<Mutex<Pipe>>,
+}
+
+impl Drop for Ends {
+    fn drop(&mut self) {
+        self.write.lock().unwrap().close();
+        self.read.lock().unwrap().reader_dropped = true;
+    }
+}
+
+/// Bytes in flight in one direction.
+#[derive(Debug, Default)]
+struct Pipe {
+    buffer: VecDeque<u8>,
+    closed: bool,
+    reader_dropped: bool,
+    waker: Option<Waker>,
+}
+
+impl Pipe {
+    fn close(&mut self) {
+        self.closed = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Read for DuplexStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut pipe = self.ends.read.lock().unwrap();
+        if pipe.buffer.is_empty() && !buf.is_empty() {
+            if pipe.closed {
+                return Poll::Ready(Ok(0));
+            }
+            pipe.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let len = buf.len().min(pipe.buffer.len());
+        for (byte, read) in buf.iter_mut().zip(pipe.buffer.drain(..len)) {
+            *byte = read;
+        }
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl Write for DuplexStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut pipe = self.ends.write.lock().unwrap();
+        if pipe.closed || pipe.reader_dropped {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        pipe.buffer.extend(buf);
+        if let Some(waker) = pipe.waker.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.ends.write.lock().unwrap().close();
+        Poll::Ready(Ok(()))
+    }
+}
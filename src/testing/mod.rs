@@ -0,0 +1,31 @@
+//! Testing applications in-process.
+//!
+//! A [`TestClient`] sends requests to a [`Server`](crate::Server) without
+//! binding a port, and checks the responses with assertion helpers.
+//!
+//! # Examples
+//!
+//! ```
+//! # async_std::task::block_on(async {
+//! use tide::testing::TestClient;
+//!
+//! let mut app = tide::new();
+//! app.at("/users/:id").get(|req: tide::Request<()>| async move {
+//!     Ok(tide::Body::from_json(&serde_json::json!({ "id": req.param("id")? }))?)
+//! });
+//!
+//! let client = TestClient::new(app);
+//! let mut res = client.get("/users/42").await?;
+//! res.assert_status(200)
+//!     .assert_header("content-type", "application/json");
+//! res.assert_json(&serde_json::json!({ "id": "42" })).await;
+//! # tide::Result::Ok(()) }).unwrap();
+//! ```
+
+mod client;
+mod duplex;
+mod response;
+
+pub use client::{TestClient, TestRequest};
+pub use duplex::{duplex, DuplexStream};
+pub use response::TestResponse;
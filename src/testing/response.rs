@@ -0,0 +1,120 @@
+use crate::http::headers::HeaderName;
+use crate::http::{self, Response};
+use crate::StatusCode;
+
+use serde::Serialize;
+
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+
+/// A response received by a [`TestClient`](super::TestClient).
+///
+/// It dereferences to the underlying [`http::Response`], and adds assertion
+/// helpers that panic with a descriptive message when they fail.
+#[derive(Debug)]
+pub struct TestResponse {
+    res: Response,
+}
+
+impl TestResponse {
+    pub(crate) fn new(res: Response) -> Self {
+        Self { res }
+    }
+
+    /// Assert that the response has `status`.
+    #[track_caller]
+    pub fn assert_status<S>(&self, status: S) -> &Self
+    where
+        S: TryInto<StatusCode>,
+        S::Error: Debug,
+    {
+        let status = status
+            .try_into()
+            .expect("Could not convert into a valid `StatusCode`");
+        assert_eq!(
+            self.res.status(),
+            status,
+            "expected status {}, got {}",
+            status,
+            self.res.status()
+        );
+        self
+    }
+
+    /// Assert that the response has the header `name` with `value`.
+    #[track_caller]
+    pub fn assert_header(&self, name: impl Into<HeaderName>, value: &str) -> &Self {
+        let name = name.into();
+        let actual = self.res.header(&name).map(|values| values.as_str());
+        assert_eq!(actual, Some(value), "unexpected value of header {}", name);
+        self
+    }
+
+    /// Assert that the response does not have the header `name`.
+    #[track_caller]
+    pub fn assert_no_header(&self, name: impl Into<HeaderName>) -> &Self {
+        let name = name.into();
+        let actual = self.res.header(&name).map(|values| values.as_str());
+        assert_eq!(actual, None, "unexpected header {}", name);
+        self
+    }
+
+    /// Assert that the body of the response is `expected`.
+    ///
+    /// This reads the body, which cannot be read again.
+    pub async fn assert_body(&mut self, expected: &str) {
+        let body = self
+            .res
+            .body_string()
+            .await
+            .expect("Could not read the response body");
+        assert_eq!(body, expected, "unexpected response body");
+    }
+
+    /// Assert that the body of the response is JSON equal to `expected`.
+    ///
+    /// The body and `expected` are compared as JSON values, so the order of
+    /// object keys and insignificant whitespace don't matter. This reads the
+    /// body, which cannot be read again.
+    pub async fn assert_json(&mut self, expected: &impl Serialize) {
+        let body: serde_json::Value = self
+            .res
+            .body_json()
+            .await
+            .expect("Could not read the response body as JSON");
+        let expected = serde_json::to_value(expected).expect("Could not serialize the JSON");
+        assert_eq!(body, expected, "unexpected JSON response body");
+    }
+
+    /// Get the underlying response.
+    pub fn into_inner(self) -> Response {
+        self.res
+    }
+}
+
+impl Deref for TestResponse {
+    type Target = Response;
+
+    fn deref(&self) -> &Self::Target {
+        &self.res
+    }
+}
+
+impl DerefMut for TestResponse {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.res
+    }
+}
+
+impl From<TestResponse> for http::Response {
+    fn from(res: TestResponse) -> Self {
+        res.res
+    }
+}
+
+impl From<TestResponse> for crate::Response {
+    fn from(res: TestResponse) -> Self {
+        res.res.into()
+    }
+}
@@ -0,0 +1,107 @@
+use crate::http::upgrade::Connection;
+use crate::Result;
+
+use async_std::task::{Context, Poll};
+use async_tungstenite::tungstenite::protocol::{CloseFrame, Role};
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::WebSocketStream;
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use serde::Serialize;
+
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+
+/// An open WebSocket connection.
+///
+/// `WebSocketConnection` is a [`Stream`] of incoming [`Message`]s and a
+/// [`Sink`] of outgoing ones. Ping frames are answered automatically, but are
+/// still yielded from the stream. To read and write from separate tasks, use
+/// [`StreamExt::split`].
+pub struct WebSocketConnection {
+    stream: WebSocketStream<Connection>,
+    protocol: Option<String>,
+}
+
+impl WebSocketConnection {
+    /// Create a new instance of `WebSocketConnection`.
+    pub(crate) async fn new(connection: Connection, protocol: Option<String>) -> Self {
+        let stream = WebSocketStream::from_raw_socket(connection, Role::Server, None).await;
+        Self { stream, protocol }
+    }
+
+    /// The subprotocol negotiated during the handshake, if any.
+    #[must_use]
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_deref()
+    }
+
+    /// Send a message.
+    pub async fn send(&mut self, message: Message) -> Result<()> {
+        self.stream.send(message).await?;
+        Ok(())
+    }
+
+    /// Send a text message.
+    pub async fn send_string(&mut self, string: String) -> Result<()> {
+        self.send(Message::Text(string)).await
+    }
+
+    /// Send a binary message.
+    pub async fn send_bytes(&mut self, bytes: Vec<u8>) -> Result<()> {
+        self.send(Message::Binary(bytes)).await
+    }
+
+    /// Serialize `json` and send it as a text message.
+    pub async fn send_json(&mut self, json: &impl Serialize) -> Result<()> {
+        self.send_string(serde_json::to_string(json)?).await
+    }
+
+    /// Send a ping message.
+    pub async fn ping(&mut self, payload: Vec<u8>) -> Result<()> {
+        self.send(Message::Ping(payload)).await
+    }
+
+    /// Start the closing handshake, optionally with a close code and reason.
+    pub async fn close(&mut self, frame: Option<CloseFrame<'static>>) -> Result<()> {
+        self.stream.close(frame).await?;
+        Ok(())
+    }
+}
+
+impl Stream for WebSocketConnection {
+    type Item = Result<Message>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream
+            .poll_next_unpin(cx)
+            .map(|message| message.map(|message| message.map_err(Into::into)))
+    }
+}
+
+impl Sink<Message> for WebSocketConnection {
+    type Error = crate::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.stream.poll_ready_unpin(cx).map_err(Into::into)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, message: Message) -> Result<()> {
+        self.stream.start_send_unpin(message).map_err(Into::into)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.stream.poll_flush_unpin(cx).map_err(Into::into)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.stream.poll_close_unpin(cx).map_err(Into::into)
+    }
+}
+
+impl Debug for WebSocketConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketConnection")
+            .field("protocol", &self.protocol)
+            .finish()
+    }
+}
@@ -0,0 +1,185 @@
+use crate::http::headers::{CONNECTION, UPGRADE};
+use crate::http::StatusCode;
+use crate::websocket::WebSocketConnection;
+use crate::{Endpoint, Middleware, Next, Request, Response, Result};
+
+use async_std::future::Future;
+use async_std::task;
+use async_tungstenite::tungstenite::handshake::derive_accept_key;
+use kv_log_macro::error;
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+const SEC_WEBSOCKET_KEY: &str = "Sec-WebSocket-Key";
+const SEC_WEBSOCKET_VERSION: &str = "Sec-WebSocket-Version";
+const SEC_WEBSOCKET_ACCEPT: &str = "Sec-WebSocket-Accept";
+const SEC_WEBSOCKET_PROTOCOL: &str = "Sec-WebSocket-Protocol";
+
+/// An endpoint that can handle WebSocket connections.
+///
+/// `WebSocket` can be used either as an [`Endpoint`], in which case requests
+/// that are not WebSocket upgrades receive `426 Upgrade Required`, or as a
+/// [`Middleware`], in which case such requests are passed on to the rest of
+/// the route.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), std::io::Error> { async_std::task::block_on(async {
+/// #
+/// use async_std::prelude::*;
+/// use tide::websocket::WebSocket;
+///
+/// let mut app = tide::new();
+/// app.at("/chat")
+///     .with(
+///         WebSocket::new(|_req, mut conn| async move {
+///             while let Some(message) = conn.next().await {
+///                 conn.send(message?).await?;
+///             }
+///             Ok(())
+///         })
+///         .with_protocols(&["chat"]),
+///     )
+///     .get(|_| async { Ok("this is not a websocket request") });
+/// app.listen("localhost:8080").await?;
+/// # Ok(()) }) }
+/// ```
+#[derive(Debug)]
+pub struct WebSocket<State, H> {
+    handler: Arc<H>,
+    protocols: Vec<String>,
+    __state: PhantomData<State>,
+}
+
+impl<State, H, Fut> WebSocket<State, H>
+where
+    State: Clone + Send + Sync + 'static,
+    H: Fn(Request<State>, WebSocketConnection) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    /// Create a new instance of `WebSocket` with the given handler.
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            protocols: Vec::new(),
+            __state: PhantomData,
+        }
+    }
+
+    /// Set the subprotocols this endpoint supports, in order of preference.
+    ///
+    /// The first of these that the client also offers is selected and can be
+    /// read from [`WebSocketConnection::protocol`]. If the client offers
+    /// subprotocols but none of them are supported, the handshake completes
+    /// without selecting one.
+    #[must_use]
+    pub fn with_protocols(mut self, protocols: &[&str]) -> Self {
+        self.protocols = protocols.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Perform the handshake, or return the request if it is not a
+    /// WebSocket upgrade request.
+    async fn upgrade(&self, req: Request<State>) -> std::result::Result<Response, Request<State>> {
+        if !is_upgrade_request(&req) {
+            return Err(req);
+        }
+
+        if header_str(&req, SEC_WEBSOCKET_VERSION) != Some("13") {
+            let mut res = Response::new(StatusCode::UpgradeRequired);
+            res.insert_header(UPGRADE, "websocket");
+            res.insert_header(SEC_WEBSOCKET_VERSION, "13");
+            return Ok(res);
+        }
+
+        let key = match header_str(&req, SEC_WEBSOCKET_KEY) {
+            Some(key) if !key.is_empty() => key,
+            _ => return Ok(Response::new(StatusCode::BadRequest)),
+        };
+
+        let protocol = header_str(&req, SEC_WEBSOCKET_PROTOCOL).and_then(|offered| {
+            let offered: Vec<_> = offered.split(',').map(str::trim).collect();
+            self.protocols
+                .iter()
+                .find(|protocol| offered.contains(&protocol.as_str()))
+                .cloned()
+        });
+
+        // Perform the handshake as described here:
+        // https://tools.ietf.org/html/rfc6455#section-4.2.2
+        let mut res = Response::new(StatusCode::SwitchingProtocols);
+        res.insert_header(UPGRADE, "websocket");
+        res.insert_header(CONNECTION, "Upgrade");
+        res.insert_header(SEC_WEBSOCKET_ACCEPT, derive_accept_key(key.as_bytes()));
+        if let Some(protocol) = &protocol {
+            res.insert_header(SEC_WEBSOCKET_PROTOCOL, protocol.as_str());
+        }
+
+        let upgrade_receiver = res.res.recv_upgrade().await;
+        let handler = self.handler.clone();
+        task::spawn(async move {
+            if let Some(connection) = upgrade_receiver.await {
+                let connection = WebSocketConnection::new(connection, protocol).await;
+                if let Err(err) = handler(req, connection).await {
+                    error!("WebSocket handler error: {:?}", err);
+                }
+            }
+        });
+
+        Ok(res)
+    }
+}
+
+fn header_str<'a, State>(req: &'a Request<State>, name: &'static str) -> Option<&'a str> {
+    req.header(name).map(|values| values.last().as_str())
+}
+
+fn is_upgrade_request<State>(req: &Request<State>) -> bool {
+    let has_token = |name, token: &str| {
+        req.header(name)
+            .into_iter()
+            .flat_map(|values| values.iter())
+            .flat_map(|value| value.as_str().split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case(token))
+    };
+
+    req.method() == crate::http::Method::Get
+        && has_token(UPGRADE, "websocket")
+        && has_token(CONNECTION, "upgrade")
+}
+
+#[async_trait::async_trait]
+impl<State, H, Fut> Endpoint<State> for WebSocket<State, H>
+where
+    State: Clone + Send + Sync + 'static,
+    H: Fn(Request<State>, WebSocketConnection) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    async fn call(&self, req: Request<State>) -> Result {
+        match self.upgrade(req).await {
+            Ok(res) => Ok(res),
+            Err(_) => {
+                let mut res = Response::new(StatusCode::UpgradeRequired);
+                res.insert_header(UPGRADE, "websocket");
+                Ok(res)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<State, H, Fut> Middleware<State> for WebSocket<State, H>
+where
+    State: Clone + Send + Sync + 'static,
+    H: Fn(Request<State>, WebSocketConnection) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    async fn handle(&self, req: Request<State>, next: Next<'_, State>) -> Result {
+        match self.upgrade(req).await {
+            Ok(res) => Ok(res),
+            Err(req) => Ok(next.run(req).await),
+        }
+    }
+}
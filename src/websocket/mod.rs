@@ -0,0 +1,37 @@
+//! WebSocket types.
+//!
+//! # Errors
+//!
+//! Errors originating in the WebSocket handler will be logged. Requests that
+//! are not valid WebSocket handshakes are rejected before the handler is
+//! called.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # fn main() -> Result<(), std::io::Error> { async_std::task::block_on(async {
+//! #
+//! use async_std::prelude::*;
+//! use tide::websocket::{Message, WebSocket};
+//!
+//! let mut app = tide::new();
+//! app.at("/echo").get(WebSocket::new(|_req, mut conn| async move {
+//!     while let Some(message) = conn.next().await {
+//!         if let Message::Text(text) = message? {
+//!             conn.send_string(text).await?;
+//!         }
+//!     }
+//!     Ok(())
+//! }));
+//! app.listen("localhost:8080").await?;
+//! # Ok(()) }) }
+//! ```
+
+mod connection;
+mod endpoint;
+
+pub use async_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+pub use async_tungstenite::tungstenite::protocol::CloseFrame;
+pub use async_tungstenite::tungstenite::Message;
+pub use connection::WebSocketConnection;
+pub use endpoint::WebSocket;
@@ -0,0 +1,114 @@
+use async_std::io::Cursor;
+use tide::extract::{handler, Json};
+use tide::http::{Method, Request, Response, Url};
+use tide::security::BodyLimit;
+use tide::{Body, StatusCode};
+
+fn post(path: &str, body: Body) -> Request {
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(Method::Post, url);
+    req.set_body(body);
+    req
+}
+
+fn streaming(len: usize) -> Body {
+    Body::from_reader(Cursor::new(vec![b'a'; len]), None)
+}
+
+fn app() -> tide::Server<()> {
+    let mut app = tide::new();
+    app.with(BodyLimit::new(10));
+    app.at("/").post(|mut req: tide::Request<()>| async move {
+        Ok(req.body_string().await?.len().to_string())
+    });
+    app.at("/json")
+        .post(|mut req: tide::Request<()>| async move {
+            let value: serde_json::Value = req.body_json().await?;
+            Ok(value.to_string())
+        });
+    app.at("/copy").post(|req: tide::Request<()>| async move {
+        let copied = async_std::io::copy(req, async_std::io::sink()).await?;
+        Ok(copied.to_string())
+    });
+    app.at("/extract")
+        .post(handler(|Json(value): Json<serde_json::Value>| async move {
+            Ok(value.to_string())
+        }));
+    app.at("/upload")
+        .body_limit(100)
+        .post(|mut req: tide::Request<()>| async move {
+            Ok(req.body_bytes().await?.len().to_string())
+        });
+    app
+}
+
+#[async_std::test]
+async fn within_limit() -> tide::Result<()> {
+    let app = app();
+    let mut res: Response = app.respond(post("/", Body::from("hello"))).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res.body_string().await?, "5");
+
+    let mut res: Response = app.respond(post("/", streaming(10))).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res.body_string().await?, "10");
+    Ok(())
+}
+
+#[async_std::test]
+async fn content_length_over_limit() -> tide::Result<()> {
+    let app = app();
+    let res: Response = app.respond(post("/", Body::from("a".repeat(11)))).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+
+    let res: Response = app
+        .respond(post("/json", Body::from(r#"{"key": "value"}"#)))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+
+    // Endpoints streaming the request are not reached.
+    let res: Response = app
+        .respond(post("/copy", Body::from("a".repeat(11))))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+    Ok(())
+}
+
+#[async_std::test]
+async fn streaming_body_over_limit() -> tide::Result<()> {
+    let app = app();
+    let res: Response = app.respond(post("/", streaming(11))).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+
+    let mut req = post("/extract", streaming(11));
+    req.set_content_type(tide::http::mime::JSON);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+    Ok(())
+}
+
+#[async_std::test]
+async fn route_limit_overrides_server_limit() -> tide::Result<()> {
+    let app = app();
+    let mut res: Response = app.respond(post("/upload", streaming(50))).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res.body_string().await?, "50");
+
+    let res: Response = app.respond(post("/upload", streaming(101))).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+
+    let mut res: Response = app
+        .respond(post("/upload", Body::from("a".repeat(50))))
+        .await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res.body_string().await?, "50");
+
+    let res: Response = app
+        .respond(post("/upload", Body::from("a".repeat(101))))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use async_compression::futures::bufread::{GzipDecoder, ZlibDecoder};
+use async_std::io::{prelude::*, BufReader, Cursor};
+use tide::compress::CompressMiddleware;
+use tide::http::headers::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, ETAG, VARY};
+use tide::http::{Method, Request, Response, Url};
+use tide::{Body, StatusCode};
+
+const TEXT: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ";
+
+fn get(path: &str, accept_encoding: Option<&str>) -> Request {
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(Method::Get, url);
+    if let Some(accept_encoding) = accept_encoding {
+        req.insert_header(ACCEPT_ENCODING, accept_encoding);
+    }
+    req
+}
+
+fn app() -> tide::Server<()> {
+    let mut app = tide::new();
+    app.with(CompressMiddleware::new());
+    app.at("/").get(|_| async {
+        let mut res = tide::Response::new(StatusCode::Ok);
+        res.set_body(TEXT.repeat(100));
+        res.insert_header(ETAG, "\"abc\"");
+        Ok(res)
+    });
+    app.at("/small").get(|_| async { Ok("small") });
+    app.at("/stream").get(|_| async {
+        let body = Body::from_reader(Cursor::new(TEXT.repeat(100)), None);
+        Ok(body)
+    });
+    app.at("/encoded").get(|_| async {
+        let mut res = tide::Response::new(StatusCode::Ok);
+        res.set_body(TEXT.repeat(100));
+        res.insert_header(CONTENT_ENCODING, "identity");
+        Ok(res)
+    });
+    app.at("/sse").get(|_| async {
+        let mut res = tide::Response::new(StatusCode::Ok);
+        res.set_body(TEXT.repeat(100));
+        res.set_content_type("text/event-stream");
+        Ok(res)
+    });
+    app
+}
+
+async fn decode(res: &mut Response) -> tide::Result<String> {
+    let body = BufReader::new(res.take_body());
+    let mut text = String::new();
+    match res[CONTENT_ENCODING].as_str() {
+        "gzip" => GzipDecoder::new(body).read_to_string(&mut text).await?,
+        "deflate" => ZlibDecoder::new(body).read_to_string(&mut text).await?,
+        encoding => panic!("unexpected encoding {}", encoding),
+    };
+    Ok(text)
+}
+
+#[async_std::test]
+async fn gzip() -> tide::Result<()> {
+    let mut res: Response = app().respond(get("/", Some("gzip"))).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res[CONTENT_ENCODING], "gzip");
+    assert_eq!(res[VARY], "Accept-Encoding");
+    assert_eq!(res[ETAG], "W/\"abc\"");
+    assert!(res.header(CONTENT_LENGTH).is_none());
+    assert_eq!(res.content_type(), Some(tide::http::mime::PLAIN));
+    assert_eq!(decode(&mut res).await?, TEXT.repeat(100));
+    Ok(())
+}
+
+#[async_std::test]
+async fn deflate() -> tide::Result<()> {
+    let mut res: Response = app().respond(get("/", Some("gzip;q=0.5, deflate"))).await?;
+    assert_eq!(res[CONTENT_ENCODING], "deflate");
+    assert_eq!(decode(&mut res).await?, TEXT.repeat(100));
+    Ok(())
+}
+
+#[async_std::test]
+async fn streaming_body() -> tide::Result<()> {
+    let mut res: Response = app().respond(get("/stream", Some("gzip"))).await?;
+    assert_eq!(res[CONTENT_ENCODING], "gzip");
+    assert_eq!(decode(&mut res).await?, TEXT.repeat(100));
+    Ok(())
+}
+
+#[async_std::test]
+async fn no_acceptable_encoding() -> tide::Result<()> {
+    for accept_encoding in [None, Some("identity"), Some("gzip;q=0, *;q=0")] {
+        let mut res: Response = app().respond(get("/", accept_encoding)).await?;
+        assert!(res.header(CONTENT_ENCODING).is_none());
+        assert_eq!(res[VARY], "Accept-Encoding");
+        assert_eq!(res[ETAG], "\"abc\"");
+        assert_eq!(res.body_string().await?, TEXT.repeat(100));
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn skipped_responses() -> tide::Result<()> {
+    let app = app();
+
+    let mut res: Response = app.respond(get("/small", Some("gzip"))).await?;
+    assert!(res.header(CONTENT_ENCODING).is_none());
+    assert!(res.header(VARY).is_none());
+    assert_eq!(res.body_string().await?, "small");
+
+    let res: Response = app.respond(get("/encoded", Some("gzip"))).await?;
+    assert_eq!(res[CONTENT_ENCODING], "identity");
+
+    let mut res: Response = app.respond(get("/sse", Some("gzip"))).await?;
+    assert!(res.header(CONTENT_ENCODING).is_none());
+    assert_eq!(res.body_string().await?, TEXT.repeat(100));
+    Ok(())
+}
+
+#[async_std::test]
+async fn threshold() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.with(CompressMiddleware::new().threshold(0));
+    app.at("/").get(|_| async { Ok("small") });
+    let res: Response = app.respond(get("/", Some("gzip"))).await?;
+    assert_eq!(res[CONTENT_ENCODING], "gzip");
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use tide::conditional::{check_preconditions, ConditionalGet};
+use tide::http::conditional::ETag;
+use tide::http::headers::{
+    HeaderName, ETAG, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_UNMODIFIED_SINCE,
+    LAST_MODIFIED,
+};
+use tide::http::{Method, Request, Response, Url};
+use tide::StatusCode;
+
+fn request(method: Method, path: &str, headers: &[(HeaderName, &str)]) -> Request {
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(method, url);
+    for (name, value) in headers {
+        req.insert_header(name, *value);
+    }
+    req
+}
+
+fn app() -> tide::Server<()> {
+    let mut app = tide::new();
+    app.with(ConditionalGet::new());
+    app.at("/").get(|_| async { Ok("hello world") });
+    app.at("/versioned").get(|_| async {
+        Ok(tide::Response::builder(200)
+            .etag(ETag::new("v1".into()))
+            .last_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+            .body("versioned"))
+    });
+    app.at("/doc").put(|req: tide::Request<()>| async move {
+        let modified = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        check_preconditions(&req, Some(&ETag::new("v1".into())), Some(modified))?;
+        Ok(StatusCode::NoContent)
+    });
+    app.at("/new").put(|req: tide::Request<()>| async move {
+        check_preconditions(&req, None, None)?;
+        Ok(StatusCode::Created)
+    });
+    app
+}
+
+#[async_std::test]
+async fn computes_etag() -> tide::Result<()> {
+    let app = app();
+    let mut res: Response = app.respond(request(Method::Get, "/", &[])).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    let etag = res[ETAG].as_str().to_owned();
+    // The tag is the length and FNV-1a hash of the body, the same in every build.
+    assert_eq!(etag, "\"b-779a65e7023cd2e7\"");
+    assert_eq!(res.body_string().await?, "hello world");
+
+    let mut res: Response = app
+        .respond(request(Method::Get, "/", &[(IF_NONE_MATCH, &etag)]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::NotModified);
+    assert_eq!(res[ETAG], etag.as_str());
+    assert_eq!(res.body_string().await?, "");
+
+    let res: Response = app
+        .respond(request(Method::Get, "/", &[(IF_NONE_MATCH, "\"other\"")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    Ok(())
+}
+
+#[async_std::test]
+async fn weak_etag() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.with(ConditionalGet::new().weak(true));
+    app.at("/").get(|_| async { Ok("hello world") });
+    let res: Response = app.respond(request(Method::Get, "/", &[])).await?;
+    let etag = res[ETAG].as_str().to_owned();
+    assert!(etag.starts_with("W/"));
+
+    let strong = etag.trim_start_matches("W/");
+    let res: Response = app
+        .respond(request(Method::Get, "/", &[(IF_NONE_MATCH, strong)]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::NotModified);
+    Ok(())
+}
+
+#[async_std::test]
+async fn endpoint_validators() -> tide::Result<()> {
+    let app = app();
+    let res: Response = app.respond(request(Method::Get, "/versioned", &[])).await?;
+    assert_eq!(res[ETAG], "\"v1\"");
+    assert_eq!(res[LAST_MODIFIED], "Mon, 12 Jan 1970 13:46:40 GMT");
+
+    let res: Response = app
+        .respond(request(
+            Method::Get,
+            "/versioned",
+            &[(IF_NONE_MATCH, "\"v0\", W/\"v1\"")],
+        ))
+        .await?;
+    assert_eq!(res.status(), StatusCode::NotModified);
+
+    let res: Response = app
+        .respond(request(
+            Method::Head,
+            "/versioned",
+            &[(IF_MODIFIED_SINCE, "Mon, 12 Jan 1970 13:46:40 GMT")],
+        ))
+        .await?;
+    assert_eq!(res.status(), StatusCode::NotModified);
+
+    let res: Response = app
+        .respond(request(
+            Method::Get,
+            "/versioned",
+            &[(IF_MODIFIED_SINCE, "Mon, 12 Jan 1970 13:46:39 GMT")],
+        ))
+        .await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+
+    // If-None-Match takes precedence over If-Modified-Since.
+    let res: Response = app
+        .respond(request(
+            Method::Get,
+            "/versioned",
+            &[
+                (IF_NONE_MATCH, "\"v0\""),
+                (IF_MODIFIED_SINCE, "Mon, 12 Jan 1970 13:46:40 GMT"),
+            ],
+        ))
+        .await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    Ok(())
+}
+
+#[async_std::test]
+async fn failed_preconditions_on_get() -> tide::Result<()> {
+    let app = app();
+    let res: Response = app
+        .respond(request(Method::Get, "/versioned", &[(IF_MATCH, "\"v0\"")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PreconditionFailed);
+
+    // If-Match uses strong comparison.
+    let res: Response = app
+        .respond(request(
+            Method::Get,
+            "/versioned",
+            &[(IF_MATCH, "W/\"v1\"")],
+        ))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PreconditionFailed);
+
+    let res: Response = app
+        .respond(request(Method::Get, "/versioned", &[(IF_MATCH, "\"v1\"")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_preconditions_on_writes() -> tide::Result<()> {
+    let app = app();
+    let res: Response = app.respond(request(Method::Put, "/doc", &[])).await?;
+    assert_eq!(res.status(), StatusCode::NoContent);
+
+    let res: Response = app
+        .respond(request(Method::Put, "/doc", &[(IF_MATCH, "\"v1\"")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::NoContent);
+
+    let res: Response = app
+        .respond(request(Method::Put, "/doc", &[(IF_MATCH, "\"v0\"")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PreconditionFailed);
+
+    let res: Response = app
+        .respond(request(
+            Method::Put,
+            "/doc",
+            &[(IF_UNMODIFIED_SINCE, "Mon, 12 Jan 1970 13:46:39 GMT")],
+        ))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PreconditionFailed);
+
+    let res: Response = app
+        .respond(request(Method::Put, "/doc", &[(IF_NONE_MATCH, "*")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PreconditionFailed);
+
+    let res: Response = app
+        .respond(request(Method::Put, "/new", &[(IF_NONE_MATCH, "*")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::Created);
+
+    let res: Response = app
+        .respond(request(Method::Put, "/new", &[(IF_MATCH, "*")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PreconditionFailed);
+    Ok(())
+}
+
+#[async_std::test]
+async fn serve_file() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("file.txt");
+    std::fs::write(&path, "contents")?;
+
+    let mut app = tide::new();
+    app.at("/file").serve_file(&path)?;
+    app.at("/dir/*").serve_dir(dir.path())?;
+
+    for url in ["/file", "/dir/file.txt"] {
+        let res: Response = app.respond(request(Method::Get, url, &[])).await?;
+        assert_eq!(res.status(), StatusCode::Ok);
+        assert!(res[ETAG].as_str().starts_with("W/"));
+        let etag = res[ETAG].as_str().to_owned();
+        let last_modified = res[LAST_MODIFIED].as_str().to_owned();
+
+        let res: Response = app
+            .respond(request(Method::Get, url, &[(IF_NONE_MATCH, &etag)]))
+            .await?;
+        assert_eq!(res.status(), StatusCode::NotModified);
+
+        let res: Response = app
+            .respond(request(
+                Method::Get,
+                url,
+                &[(IF_MODIFIED_SINCE, &last_modified)],
+            ))
+            .await?;
+        assert_eq!(res.status(), StatusCode::NotModified);
+    }
+
+    Ok(())
+}
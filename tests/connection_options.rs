@@ -0,0 +1,127 @@
+use async_std::prelude::*;
+use async_std::task::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use tide::listener::{ConnectionInfo, ConnectionOptions};
+use tide::testing::{duplex, DuplexStream};
+
+const REQUEST: &[u8] = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+
+fn serve(options: ConnectionOptions) -> (DuplexStream, JoinHandle<tide::Result<()>>) {
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("hello") });
+    let (client, stream) = duplex();
+    let info = ConnectionInfo::new().options(options);
+    let server = task::spawn(async move { app.serve_connection(stream, info).await });
+    (client, server)
+}
+
+/// Send a request and read its response, whose body is `hello`.
+async fn request(client: &mut DuplexStream) -> tide::Result<String> {
+    client.write_all(REQUEST).await?;
+    let mut res = String::new();
+    let mut buf = [0; 1024];
+    while !res.ends_with("hello") {
+        let len = client.read(&mut buf).await?;
+        assert!(len > 0, "unexpected end of response: {}", res);
+        res.push_str(std::str::from_utf8(&buf[..len])?);
+    }
+    Ok(res)
+}
+
+/// Read until the server closes the connection.
+async fn closed(client: &mut DuplexStream) -> tide::Result<()> {
+    let mut rest = Vec::new();
+    client.read_to_end(&mut rest).await?;
+    assert!(rest.is_empty());
+    Ok(())
+}
+
+#[async_std::test]
+async fn keep_alive() -> tide::Result<()> {
+    let (mut client, server) = serve(ConnectionOptions::new());
+    assert!(!request(&mut client).await?.contains("connection: close"));
+    assert!(!request(&mut client).await?.contains("connection: close"));
+    drop(client);
+    server.await?;
+
+    let (mut client, server) = serve(ConnectionOptions::new().keep_alive(false));
+    assert!(request(&mut client).await?.contains("connection: close"));
+    closed(&mut client).await?;
+    server.await?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn max_requests() -> tide::Result<()> {
+    let (mut client, server) = serve(ConnectionOptions::new().max_requests(2));
+    assert!(!request(&mut client).await?.contains("connection: close"));
+    assert!(request(&mut client).await?.contains("connection: close"));
+    closed(&mut client).await?;
+    server.await?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn header_read_timeout() -> tide::Result<()> {
+    let options = ConnectionOptions::new().header_read_timeout(Duration::from_millis(100));
+    let (mut client, server) = serve(options);
+    let start = Instant::now();
+    client.write_all(b"GET / HTTP/1.1\r\nHost: ex").await?;
+    closed(&mut client).await?;
+    server.await?;
+    assert!(start.elapsed() < Duration::from_secs(10));
+    Ok(())
+}
+
+#[async_std::test]
+async fn keep_alive_timeout() -> tide::Result<()> {
+    let options = ConnectionOptions::new().keep_alive_timeout(Duration::from_millis(100));
+    let (mut client, server) = serve(options);
+    request(&mut client).await?;
+    let start = Instant::now();
+    closed(&mut client).await?;
+    server.await?;
+    assert!(start.elapsed() < Duration::from_secs(10));
+    Ok(())
+}
+
+#[async_std::test]
+async fn max_header_size() -> tide::Result<()> {
+    let (mut client, server) = serve(ConnectionOptions::new().max_header_size(128));
+    request(&mut client).await?;
+
+    let head = format!(
+        "GET / HTTP/1.1\r\nHost: example.com\r\nX-Padding: {}\r\n\r\n",
+        "x".repeat(128)
+    );
+    client.write_all(head.as_bytes()).await?;
+    closed(&mut client).await?;
+    assert!(server.await.is_err());
+    Ok(())
+}
+
+#[async_std::test]
+async fn tcp_listener() -> tide::Result<()> {
+    use async_std::net::TcpStream;
+    use tide::listener::{Listener, TcpListener};
+
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("hello") });
+    let options = ConnectionOptions::new()
+        .keep_alive(false)
+        .nodelay(true)
+        .accept_backoff(Duration::from_millis(10));
+    let listener = TcpListener::from_addrs(vec!["127.0.0.1:0".parse()?]).with_options(options);
+    let mut listener = app.bind(listener).await?;
+    let addr = listener.local_addrs()[0].tcp().unwrap();
+    task::spawn(async move { listener.accept().await });
+
+    let mut stream = TcpStream::connect(addr).await?;
+    stream.write_all(REQUEST).await?;
+    let mut res = String::new();
+    stream.read_to_string(&mut res).await?;
+    assert!(res.contains("connection: close"));
+    assert!(res.ends_with("hello"));
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+use tide::extract::{handler, Ext, Form, Header, Json, Path, Query, State};
+use tide::http::content::ContentType;
+use tide::http::{mime, Method, Request, Response, Url};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Cat {
+    name: String,
+    age: u8,
+}
+
+fn request(method: Method, path: &str) -> Request {
+    Request::new(
+        method,
+        Url::parse("http://example.com")
+            .unwrap()
+            .join(path)
+            .unwrap(),
+    )
+}
+
+#[async_std::test]
+async fn json_extractor() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/").post(handler(|Json(cat): Json<Cat>| async move {
+        Ok(format!("{} is {}", cat.name, cat.age))
+    }));
+
+    let mut req = request(Method::Post, "/");
+    req.set_body(tide::Body::from_json(&Cat {
+        name: "chashu".into(),
+        age: 3,
+    })?);
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body_string().await?, "chashu is 3");
+
+    let mut req = request(Method::Post, "/");
+    req.set_body(r#"{"name":"chashu"}"#);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 415);
+
+    let mut req = request(Method::Post, "/");
+    req.set_body(r#"{"name":"chashu"}"#);
+    req.set_content_type(mime::JSON);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 422);
+
+    let mut req = request(Method::Post, "/");
+    req.set_body("{not json");
+    req.set_content_type(mime::JSON);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 400);
+
+    let mut req = request(Method::Post, "/");
+    req.set_body(broken_body());
+    req.set_content_type(mime::JSON);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 400);
+    Ok(())
+}
+
+/// A body that fails to be read.
+fn broken_body() -> tide::Body {
+    struct Broken;
+    impl async_std::io::Read for Broken {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            _: &mut [u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            let error = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+            std::task::Poll::Ready(Err(error))
+        }
+    }
+    tide::Body::from_reader(async_std::io::BufReader::new(Broken), None)
+}
+
+#[async_std::test]
+async fn form_extractor() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/")
+        .post(handler(|Form(cat): Form<Cat>| async move { Ok(cat.name) }));
+
+    let mut req = request(Method::Post, "/");
+    req.set_body(tide::Body::from_form(&Cat {
+        name: "nori".into(),
+        age: 5,
+    })?);
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(res.body_string().await?, "nori");
+
+    let mut req = request(Method::Post, "/");
+    req.set_body("name=nori&age=5");
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 415);
+
+    let mut req = request(Method::Post, "/");
+    req.set_body("name=nori&age=old");
+    req.set_content_type(mime::FORM);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 422);
+
+    let mut req = request(Method::Post, "/");
+    req.set_body(broken_body());
+    req.set_content_type(mime::FORM);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 400);
+    Ok(())
+}
+
+#[async_std::test]
+async fn query_and_path_extractors() -> tide::Result<()> {
+    #[derive(Deserialize)]
+    struct Params {
+        user: String,
+        id: u32,
+    }
+
+    let mut app = tide::new();
+    app.at("/users/:user/cats/:id").get(handler(
+        |Path(params): Path<Params>, Query(cat): Query<Cat>| async move {
+            Ok(format!(
+                "{} {} {} {}",
+                params.user, params.id, cat.name, cat.age
+            ))
+        },
+    ));
+    app.at("/tuple/:a/:b")
+        .get(handler(|Path((a, b)): Path<(String, u8)>| async move {
+            Ok(format!("{}-{}", a, b))
+        }));
+    app.at("/single/:id").get(handler(
+        |Path(id): Path<u64>| async move { Ok(id.to_string()) },
+    ));
+
+    let req = request(Method::Get, "/users/ada/cats/7?name=nori&age=2");
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(res.body_string().await?, "ada 7 nori 2");
+
+    let req = request(Method::Get, "/users/ada/cats/7?name=nori");
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 400);
+
+    let req = request(Method::Get, "/users/ada/cats/seven?name=nori&age=2");
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 400);
+
+    let req = request(Method::Get, "/tuple/x/9");
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(res.body_string().await?, "x-9");
+
+    let req = request(Method::Get, "/single/42");
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(res.body_string().await?, "42");
+    Ok(())
+}
+
+fn answer_middleware<'a, State: Clone + Send + Sync + 'static>(
+    mut req: tide::Request<State>,
+    next: tide::Next<'a, State>,
+) -> Pin<Box<dyn Future<Output = tide::Result> + 'a + Send>> {
+    req.set_ext(42_u32);
+    Box::pin(async move { Ok(next.run(req).await) })
+}
+
+#[async_std::test]
+async fn state_header_and_ext_extractors() -> tide::Result<()> {
+    #[derive(Clone)]
+    struct Greeting(&'static str);
+
+    let mut app = tide::with_state(Greeting("hello"));
+    app.with(answer_middleware);
+    app.at("/").get(handler(
+        |State(greeting): State<Greeting>,
+         Header(content_type): Header<ContentType>,
+         Ext(answer): Ext<u32>| async move {
+            Ok(format!(
+                "{} {} {}",
+                greeting.0,
+                content_type.value(),
+                answer
+            ))
+        },
+    ));
+    app.at("/missing-ext")
+        .get(handler(|Ext(value): Ext<String>| async move { Ok(value) }));
+    app.at("/optional").get(handler(
+        |content_type: Option<Header<ContentType>>| async move {
+            Ok(content_type.is_some().to_string())
+        },
+    ));
+
+    let mut req = request(Method::Get, "/");
+    req.set_content_type(mime::PLAIN);
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(
+        res.body_string().await?,
+        "hello text/plain;charset=utf-8 42"
+    );
+
+    let req = request(Method::Get, "/");
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 400);
+
+    let req = request(Method::Get, "/missing-ext");
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), 500);
+
+    let req = request(Method::Get, "/optional");
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(res.body_string().await?, "false");
+    Ok(())
+}
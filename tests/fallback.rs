@@ -0,0 +1,125 @@
+mod test_utils;
+use test_utils::ServerTestingExt;
+use tide::{Request, Response, StatusCode};
+
+async fn branded_not_found<State>(req: Request<State>) -> tide::Result {
+    Ok(Response::builder(StatusCode::NotFound)
+        .body(format!("no page at {}", req.url().path()))
+        .build())
+}
+
+#[async_std::test]
+async fn default_fallbacks() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("root") });
+
+    assert_eq!(app.get("/missing").await?.status(), StatusCode::NotFound);
+    assert_eq!(app.post("/").await?.status(), StatusCode::MethodNotAllowed);
+    Ok(())
+}
+
+#[async_std::test]
+async fn custom_not_found_runs_middleware() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.with(tide::utils::After(|mut res: Response| async move {
+        res.insert_header("x-middleware", "ran");
+        Ok(res)
+    }));
+    app.at("/").get(|_| async { Ok("root") });
+    app.not_found(branded_not_found);
+
+    let mut res = app.get("/missing").await?;
+    assert_eq!(res.status(), StatusCode::NotFound);
+    assert_eq!(res["x-middleware"], "ran");
+    assert_eq!(res.body_string().await?, "no page at /missing");
+    Ok(())
+}
+
+#[async_std::test]
+async fn custom_method_not_allowed() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("root") });
+    app.method_not_allowed(|req: Request<()>| async move {
+        Ok(Response::builder(StatusCode::MethodNotAllowed)
+            .body(format!("{} is not allowed", req.method())))
+    });
+
+    let mut res = app.post("/").await?;
+    assert_eq!(res.status(), StatusCode::MethodNotAllowed);
+    assert_eq!(res.body_string().await?, "POST is not allowed");
+    assert_eq!(app.get("/missing").await?.status(), StatusCode::NotFound);
+    Ok(())
+}
+
+#[async_std::test]
+async fn nested_not_found() -> tide::Result<()> {
+    let mut api = tide::new();
+    api.at("/cats").get(|_| async { Ok("cats") });
+    api.not_found(|_| async {
+        Ok(Response::builder(StatusCode::NotFound).body("api route not found"))
+    });
+
+    let mut app = tide::new();
+    app.at("/api").nest(api);
+    app.not_found(branded_not_found);
+
+    let mut res = app.get("/api/dogs").await?;
+    assert_eq!(res.status(), StatusCode::NotFound);
+    assert_eq!(res.body_string().await?, "api route not found");
+
+    let mut res = app.get("/dogs").await?;
+    assert_eq!(res.status(), StatusCode::NotFound);
+    assert_eq!(res.body_string().await?, "no page at /dogs");
+    Ok(())
+}
+
+fn allowed(res: &tide::http::Response) -> Vec<String> {
+    let allow = res.header("allow").expect("an Allow header").as_str();
+    let mut methods: Vec<_> = allow.split(", ").map(str::to_owned).collect();
+    methods.sort();
+    methods
+}
+
+#[async_std::test]
+async fn method_not_allowed_sends_allow_header() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("root") });
+    app.at("/").put(|_| async { Ok("root") });
+    app.at("/other").delete(|_| async { Ok("other") });
+
+    let res = app.post("/").await?;
+    assert_eq!(res.status(), StatusCode::MethodNotAllowed);
+    assert_eq!(allowed(res.as_ref()), ["GET", "HEAD", "OPTIONS", "PUT"]);
+
+    let res = app.get("/other").await?;
+    assert_eq!(res.status(), StatusCode::MethodNotAllowed);
+    assert_eq!(allowed(res.as_ref()), ["DELETE", "OPTIONS"]);
+
+    app.method_not_allowed(|_| async { Ok(Response::new(StatusCode::MethodNotAllowed)) });
+    let res = app.post("/").await?;
+    assert_eq!(allowed(res.as_ref()), ["GET", "HEAD", "OPTIONS", "PUT"]);
+    Ok(())
+}
+
+#[async_std::test]
+async fn automatic_options() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("root") });
+    app.at("/custom").post(|_| async { Ok("custom") });
+    app.at("/custom")
+        .options(|_| async { Ok(Response::builder(StatusCode::Ok).header("allow", "POST")) });
+
+    let res = app.options("/").await?;
+    assert_eq!(res.status(), StatusCode::NoContent);
+    assert_eq!(allowed(res.as_ref()), ["GET", "HEAD", "OPTIONS"]);
+
+    let res = app.options("/custom").await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(allowed(res.as_ref()), ["POST"]);
+
+    assert_eq!(
+        app.options("/missing").await?.status(),
+        StatusCode::NotFound
+    );
+    Ok(())
+}
@@ -0,0 +1,245 @@
+use tide::fs::{FileReader, FileSystem, MemoryFs, Metadata, ServeDir, ServeFile};
+use tide::http::headers::{HeaderName, CONTENT_RANGE, ETAG, LAST_MODIFIED};
+use tide::http::{mime, Method, Request, Response, Url};
+use tide::{Server, StatusCode};
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn request(path: &str, headers: &[(&str, &str)]) -> Request {
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(Method::Get, url);
+    for (name, value) in headers {
+        req.insert_header(HeaderName::from(*name), *value);
+    }
+    req
+}
+
+fn memory_fs() -> MemoryFs {
+    MemoryFs::new()
+        .file("index.html", "<h1>home</h1>")
+        .file("/css/site.css", "body {}")
+        .file("docs/a b.txt", &b"0123456789"[..])
+        .file("docs/guide/intro.md", "# Intro")
+        .modified(UNIX_EPOCH + Duration::from_secs(1_000_000_000))
+}
+
+fn app(fs: MemoryFs) -> Server<()> {
+    let mut app = tide::new();
+    app.at("/*")
+        .serve_dir_with(ServeDir::from_fs(fs).autoindex(true));
+    app
+}
+
+#[async_std::test]
+async fn memory_fs_serves_files() -> tide::Result<()> {
+    let app = app(memory_fs());
+
+    let mut res: Response = app.respond(request("/", &[])).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res.content_type(), Some(mime::HTML));
+    assert_eq!(res.body_string().await?, "<h1>home</h1>");
+
+    let mut res: Response = app.respond(request("/css/site.css", &[])).await?;
+    assert_eq!(res.content_type(), Some(mime::CSS));
+    assert_eq!(res[LAST_MODIFIED], "Sun, 09 Sep 2001 01:46:40 GMT");
+    assert_eq!(res.body_string().await?, "body {}");
+
+    let res: Response = app.respond(request("/css/missing.css", &[])).await?;
+    assert_eq!(res.status(), StatusCode::NotFound);
+
+    let res: Response = app.respond(request("/css", &[])).await?;
+    assert_eq!(res.status(), StatusCode::PermanentRedirect);
+    Ok(())
+}
+
+#[async_std::test]
+async fn memory_fs_conditional_and_range() -> tide::Result<()> {
+    let app = app(memory_fs());
+
+    let res: Response = app.respond(request("/docs/a%20b.txt", &[])).await?;
+    let etag = res[ETAG].as_str().to_owned();
+    let res: Response = app
+        .respond(request("/docs/a%20b.txt", &[("If-None-Match", &etag)]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::NotModified);
+
+    let mut res: Response = app
+        .respond(request("/docs/a%20b.txt", &[("Range", "bytes=2-4")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PartialContent);
+    assert_eq!(res[CONTENT_RANGE], "bytes 2-4/10");
+    assert_eq!(res.body_string().await?, "234");
+
+    let mut res: Response = app
+        .respond(request("/docs/a%20b.txt", &[("Range", "bytes=0-0,-1")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PartialContent);
+    let body = res.body_string().await?;
+    assert!(body.contains("Content-Range: bytes 0-0/10\r\n\r\n0\r\n"));
+    assert!(body.contains("Content-Range: bytes 9-9/10\r\n\r\n9\r\n"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn memory_fs_listing() -> tide::Result<()> {
+    let app = app(memory_fs());
+
+    let mut res: Response = app.respond(request("/docs/", &[])).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    let body = res.body_string().await?;
+    assert!(body.contains(r#"<a href="a%20b.txt">a b.txt</a></td><td>10</td>"#));
+
+    let mut res: Response = app
+        .respond(request("/docs/", &[("Accept", "application/json")]))
+        .await?;
+    let entries: serde_json::Value = res.body_json().await?;
+    let names: Vec<_> = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["guide", "a b.txt"]);
+    Ok(())
+}
+
+#[async_std::test]
+async fn encoded_separators_are_forbidden() -> tide::Result<()> {
+    let app = app(memory_fs());
+
+    for path in ["/docs/..%2Findex.html", "/docs/%5C..%5Cindex.html"] {
+        let res: Response = app.respond(request(path, &[])).await?;
+        assert_eq!(res.status(), StatusCode::Forbidden, "{}", path);
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn disk_fs_decodes_paths() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("a b.txt"), "spaced")?;
+
+    let mut app = tide::new();
+    app.at("/*").serve_dir(dir.path())?;
+    let mut res: Response = app.respond(request("/a%20b.txt", &[])).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res.body_string().await?, "spaced");
+    Ok(())
+}
+
+#[async_std::test]
+async fn serve_file_from_fs() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/style")
+        .serve_file_with(ServeFile::from_fs(memory_fs(), "css/site.css"));
+    let mut res: Response = app.respond(request("/style", &[])).await?;
+    assert_eq!(res.content_type(), Some(mime::CSS));
+    assert_eq!(res.body_string().await?, "body {}");
+    Ok(())
+}
+
+#[async_std::test]
+async fn memory_fs_sniffs_mime() -> tide::Result<()> {
+    let fs = MemoryFs::new().file("image", &b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"[..]);
+    let res: Response = app(fs).respond(request("/image", &[])).await?;
+    assert_eq!(res.content_type(), Some(mime::PNG));
+    Ok(())
+}
+
+#[cfg(feature = "fs-tar")]
+#[async_std::test]
+async fn tar_archive() -> tide::Result<()> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, contents) in [("site/index.html", "tar index"), ("site/js/app.js", "1")] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(1_000_000_000);
+        header.set_cksum();
+        builder.append_data(&mut header, path, contents.as_bytes())?;
+    }
+    let archive = builder.into_inner()?;
+
+    let app = app(MemoryFs::from_tar(&archive[..])?);
+    let mut res: Response = app.respond(request("/site/", &[])).await?;
+    assert_eq!(res.body_string().await?, "tar index");
+    let mut res: Response = app.respond(request("/site/js/app.js", &[])).await?;
+    assert_eq!(res[LAST_MODIFIED], "Sun, 09 Sep 2001 01:46:40 GMT");
+    assert_eq!(res.body_string().await?, "1");
+    Ok(())
+}
+
+#[cfg(feature = "fs-zip")]
+#[async_std::test]
+async fn zip_archive() -> tide::Result<()> {
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default()
+        .last_modified_time(zip::DateTime::from_date_and_time(2020, 2, 29, 12, 30, 0).unwrap());
+    writer.add_directory("empty/", options)?;
+    writer.start_file("site/index.html", options)?;
+    writer.write_all(b"zip index")?;
+    let archive = writer.finish()?.into_inner();
+
+    let app = app(MemoryFs::from_zip(Cursor::new(archive))?);
+    let mut res: Response = app.respond(request("/site/", &[])).await?;
+    assert_eq!(res[LAST_MODIFIED], "Sat, 29 Feb 2020 12:30:00 GMT");
+    assert_eq!(res.body_string().await?, "zip index");
+
+    let res: Response = app.respond(request("/empty/", &[])).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    Ok(())
+}
+
+/// A file system counting the files read from it.
+struct CountingFs {
+    inner: MemoryFs,
+    reads: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl FileSystem for CountingFs {
+    async fn metadata(&self, path: &str) -> std::io::Result<Metadata> {
+        self.inner.metadata(path).await
+    }
+
+    async fn read(&self, path: &str, range: Range<u64>) -> std::io::Result<FileReader> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.read(path, range).await
+    }
+
+    async fn read_dir(&self, path: &str) -> std::io::Result<Vec<String>> {
+        self.inner.read_dir(path).await
+    }
+}
+
+#[async_std::test]
+async fn not_modified_without_reading() -> tide::Result<()> {
+    let reads = Arc::new(AtomicUsize::new(0));
+    let fs = CountingFs {
+        inner: memory_fs(),
+        reads: reads.clone(),
+    };
+    let mut app = tide::new();
+    app.at("/*").serve_dir_with(ServeDir::from_fs(fs));
+
+    let res: Response = app.respond(request("/index.html", &[])).await?;
+    let etag = res[ETAG].as_str().to_owned();
+    let reads_before = reads.load(Ordering::SeqCst);
+    assert!(reads_before > 0);
+
+    let res: Response = app
+        .respond(request("/index.html", &[("If-None-Match", &etag)]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::NotModified);
+    assert_eq!(reads.load(Ordering::SeqCst), reads_before);
+    Ok(())
+}
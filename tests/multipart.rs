@@ -0,0 +1,233 @@
+use async_std::io::{self, prelude::*};
+use tide::http::{mime, Method, Request, Response, Url};
+use tide::multipart::Limits;
+use tide::{Body, StatusCode};
+
+const BOUNDARY: &str = "X-TIDE-BOUNDARY";
+
+fn multipart_request(path: &str, parts: &[(&str, Option<&str>, &str)]) -> Request {
+    let mut body = String::new();
+    for (name, file_name, value) in parts {
+        body.push_str(&format!("--{}\r\n", BOUNDARY));
+        match file_name {
+            Some(file_name) => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: text/plain\r\n",
+                name, file_name
+            )),
+            None => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{}\"\r\n",
+                name
+            )),
+        }
+        body.push_str(&format!("\r\n{}\r\n", value));
+    }
+    body.push_str(&format!("--{}--\r\n", BOUNDARY));
+
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(Method::Post, url);
+    req.set_body(body);
+    req.insert_header(
+        "content-type",
+        format!("multipart/form-data; boundary={}", BOUNDARY),
+    );
+    req
+}
+
+fn app() -> tide::Server<()> {
+    let mut app = tide::new();
+    app.at("/stream")
+        .post(|mut req: tide::Request<()>| async move {
+            let mut multipart = req.body_multipart()?;
+            let mut summary = vec![];
+            while let Some(mut part) = multipart.next_part().await? {
+                let name = part.name().unwrap_or_default().to_owned();
+                let file_name = part.file_name().map(ToOwned::to_owned);
+                let mut contents = String::new();
+                part.read_to_string(&mut contents).await?;
+                summary.push(format!("{}:{:?}:{}", name, file_name, contents));
+            }
+            Ok(summary.join(","))
+        });
+    app.at("/form")
+        .post(|mut req: tide::Request<()>| async move {
+            let form = req
+                .body_multipart()?
+                .into_form(Limits::new().field_size(16).file_size(32).total_size(40))
+                .await?;
+            let file = form.file("upload").expect("an uploaded file");
+            let contents = async_std::fs::read_to_string(file.path()).await?;
+            Ok(format!(
+                "{} {:?} {} {} {}",
+                form.field("title").unwrap_or_default(),
+                file.file_name(),
+                file.content_type()
+                    .map(|mime| mime.essence().to_owned())
+                    .unwrap_or_default(),
+                file.size(),
+                contents
+            ))
+        });
+    app
+}
+
+#[async_std::test]
+async fn streaming_parts() -> tide::Result<()> {
+    let req = multipart_request(
+        "/stream",
+        &[
+            ("title", None, "hello"),
+            ("upload", Some("a.txt"), "file contents"),
+        ],
+    );
+    let mut res: Response = app().respond(req).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(
+        res.body_string().await?,
+        r#"title:None:hello,upload:Some("a.txt"):file contents"#
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn buffered_form() -> tide::Result<()> {
+    let req = multipart_request(
+        "/form",
+        &[
+            ("title", None, "hello"),
+            ("upload", Some("a.txt"), "file contents"),
+        ],
+    );
+    let mut res: Response = app().respond(req).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(
+        res.body_string().await?,
+        r#"hello Some("a.txt") text/plain 13 file contents"#
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn form_limits() -> tide::Result<()> {
+    let app = app();
+
+    let long_field = "a".repeat(17);
+    let req = multipart_request("/form", &[("title", None, &long_field)]);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+
+    let long_file = "a".repeat(33);
+    let req = multipart_request("/form", &[("upload", Some("a.txt"), &long_file)]);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+
+    let file = "a".repeat(30);
+    let req = multipart_request(
+        "/form",
+        &[
+            ("title", None, "0123456789ab"),
+            ("upload", Some("a.txt"), &file),
+        ],
+    );
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+    Ok(())
+}
+
+#[async_std::test]
+async fn not_multipart() -> tide::Result<()> {
+    let url = Url::parse("http://example.com/stream")?;
+    let mut req = Request::new(Method::Post, url);
+    req.set_body(Body::from_string("hello".into()));
+    req.set_content_type(mime::PLAIN);
+    let res: Response = app().respond(req).await?;
+    assert_eq!(res.status(), StatusCode::UnsupportedMediaType);
+
+    let mut req = multipart_request("/stream", &[("title", None, "hello")]);
+    req.insert_header("content-type", "multipart/form-data");
+    let res: Response = app().respond(req).await?;
+    assert_eq!(res.status(), StatusCode::BadRequest);
+    Ok(())
+}
+
+#[async_std::test]
+async fn part_reader_copies() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/").post(|mut req: tide::Request<()>| async move {
+        let mut multipart = req.body_multipart()?;
+        let mut total = 0;
+        while let Some(part) = multipart.next_part().await? {
+            total += io::copy(part, io::sink()).await?;
+        }
+        Ok(total.to_string())
+    });
+
+    let big = "b".repeat(100_000);
+    let req = multipart_request("/", &[("a", None, "12345"), ("b", Some("big"), &big)]);
+    let mut res: Response = app.respond(req).await?;
+    assert_eq!(res.body_string().await?, "100005");
+    Ok(())
+}
+
+/// A body that is received in small chunks, so parts are read while it
+/// streams in.
+fn trickle(body: Vec<u8>) -> Body {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    struct Trickle {
+        body: Vec<u8>,
+        pos: usize,
+        ready: bool,
+    }
+
+    impl io::Read for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let len = buf.len().min(1024).min(self.body.len() - self.pos);
+            buf[..len].copy_from_slice(&self.body[self.pos..self.pos + len]);
+            self.pos += len;
+            Poll::Ready(Ok(len))
+        }
+    }
+
+    let reader = Trickle {
+        body,
+        pos: 0,
+        ready: false,
+    };
+    Body::from_reader(io::BufReader::new(reader), None)
+}
+
+#[async_std::test]
+async fn part_reader_over_body_limit() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.with(tide::security::BodyLimit::new(16 * 1024));
+    app.at("/").post(|mut req: tide::Request<()>| async move {
+        let mut multipart = req.body_multipart()?;
+        while let Some(part) = multipart.next_part().await? {
+            io::copy(part, io::sink()).await?;
+        }
+        Ok("copied")
+    });
+
+    let big = "b".repeat(64 * 1024);
+    let mut req = multipart_request("/", &[("b", Some("big"), &big)]);
+    let content_type = req["content-type"].clone();
+    let body = req.take_body().into_bytes().await?;
+    req.set_body(trickle(body));
+    req.insert_header("content-type", &content_type);
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), StatusCode::PayloadTooLarge);
+    Ok(())
+}
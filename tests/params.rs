@@ -37,3 +37,65 @@ async fn hello_world_parametrized() -> Result<()> {
     assert_eq!(res.body_string().await?, "iron says hello");
     Ok(())
 }
+
+#[async_std::test]
+async fn param_as() -> Result<()> {
+    async fn double(req: Request<()>) -> Result<String> {
+        let n: u64 = req.param_as("n")?;
+        Ok((n * 2).to_string())
+    }
+
+    let mut server = tide::new();
+    server.at("/double/:n").get(double);
+    server.with(tide::utils::After(|mut res: Response| async move {
+        if let Some(err) = res.error() {
+            let message = err.to_string();
+            res.set_body(message);
+        }
+        Ok(res)
+    }));
+
+    let req = http_types::Request::new(Method::Get, Url::parse("http://example.com/double/21")?);
+    let mut res: http_types::Response = server.respond(req).await?;
+    assert_eq!(res.body_string().await?, "42");
+
+    let req = http_types::Request::new(Method::Get, Url::parse("http://example.com/double/x")?);
+    let mut res: http_types::Response = server.respond(req).await?;
+    assert_eq!(res.status(), 400);
+    assert!(res.body_string().await?.contains("`n`"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn params_across_nested_routers() -> Result<()> {
+    #[derive(serde::Deserialize)]
+    struct Params {
+        user: String,
+        id: u32,
+    }
+
+    async fn get_cat(req: Request<()>) -> Result<String> {
+        let Params { user, id } = req.params()?;
+        Ok(format!("{}/{}", user, id))
+    }
+
+    let mut cats = tide::new();
+    cats.at("/cats/:id").get(get_cat);
+    let mut server = tide::new();
+    server.at("/users/:user").nest(cats);
+
+    let req = http_types::Request::new(
+        Method::Get,
+        Url::parse("http://example.com/users/ada/cats/7")?,
+    );
+    let mut res: http_types::Response = server.respond(req).await?;
+    assert_eq!(res.body_string().await?, "ada/7");
+
+    let req = http_types::Request::new(
+        Method::Get,
+        Url::parse("http://example.com/users/ada/cats/seven")?,
+    );
+    let res: http_types::Response = server.respond(req).await?;
+    assert_eq!(res.status(), 400);
+    Ok(())
+}
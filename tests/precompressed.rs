@@ -0,0 +1,101 @@
+use tide::fs::{ServeDir, ServeFile};
+use tide::http::headers::{ACCEPT_ENCODING, CONTENT_ENCODING, ETAG, IF_NONE_MATCH, VARY};
+use tide::http::{Method, Request, Response, Url};
+use tide::StatusCode;
+
+use std::fs;
+
+fn get(path: &str, accept_encoding: Option<&str>) -> Request {
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(Method::Get, url);
+    if let Some(accept_encoding) = accept_encoding {
+        req.insert_header(ACCEPT_ENCODING, accept_encoding);
+    }
+    req
+}
+
+fn app(dir: &tempfile::TempDir) -> tide::Result<tide::Server<()>> {
+    fs::write(dir.path().join("app.js"), "plain")?;
+    fs::write(dir.path().join("app.js.br"), "brotli")?;
+    fs::write(dir.path().join("app.js.gz"), "gzip")?;
+    fs::write(dir.path().join("style.css"), "plain")?;
+
+    let mut app = tide::new();
+    app.at("/assets/*")
+        .serve_dir_with(ServeDir::new(dir.path())?.precompressed(true));
+    app.at("/app.js")
+        .serve_file_with(ServeFile::new(dir.path().join("app.js"))?.precompressed(true));
+    app.at("/plain/*").serve_dir(dir.path())?;
+    Ok(app)
+}
+
+#[async_std::test]
+async fn serves_preferred_variant() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    let plain: Response = app.respond(get("/assets/app.js", None)).await?;
+
+    for path in ["/assets/app.js", "/app.js"] {
+        let mut res: Response = app.respond(get(path, Some("gzip, br"))).await?;
+        assert_eq!(res.status(), StatusCode::Ok);
+        assert_eq!(res[CONTENT_ENCODING], "br");
+        assert_eq!(res[VARY], "Accept-Encoding");
+        assert_eq!(res.content_type(), plain.content_type());
+        assert_eq!(res.body_string().await?, "brotli");
+
+        let mut res: Response = app.respond(get(path, Some("gzip, br;q=0.5, zstd"))).await?;
+        assert_eq!(res[CONTENT_ENCODING], "gzip");
+        assert_eq!(res.body_string().await?, "gzip");
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn falls_back_to_plain_file() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+
+    for accept_encoding in [
+        None,
+        Some("identity"),
+        Some("zstd"),
+        Some("br;q=0, gzip;q=0"),
+    ] {
+        let mut res: Response = app.respond(get("/assets/app.js", accept_encoding)).await?;
+        assert!(res.header(CONTENT_ENCODING).is_none());
+        assert_eq!(res[VARY], "Accept-Encoding");
+        assert_eq!(res.body_string().await?, "plain");
+    }
+
+    // No variants exist, so the response does not vary.
+    let mut res: Response = app.respond(get("/assets/style.css", Some("br"))).await?;
+    assert!(res.header(CONTENT_ENCODING).is_none());
+    assert!(res.header(VARY).is_none());
+    assert_eq!(res.body_string().await?, "plain");
+
+    // Precompressed variants are opt-in.
+    let mut res: Response = app.respond(get("/plain/app.js", Some("br"))).await?;
+    assert!(res.header(CONTENT_ENCODING).is_none());
+    assert_eq!(res.body_string().await?, "plain");
+    Ok(())
+}
+
+#[async_std::test]
+async fn variants_have_their_own_etags() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+
+    let plain: Response = app.respond(get("/assets/app.js", None)).await?;
+    let brotli: Response = app.respond(get("/assets/app.js", Some("br"))).await?;
+    assert_ne!(plain[ETAG].as_str(), brotli[ETAG].as_str());
+
+    let mut req = get("/assets/app.js", Some("br"));
+    req.insert_header(IF_NONE_MATCH, brotli[ETAG].as_str());
+    let res: Response = app.respond(req).await?;
+    assert_eq!(res.status(), StatusCode::NotModified);
+    assert_eq!(res[VARY], "Accept-Encoding");
+    Ok(())
+}
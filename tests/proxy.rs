@@ -0,0 +1,195 @@
+use tide::http::{self, Method, Request, Response, Url};
+use tide::proxy::Proxy;
+use tide::{Server, StatusCode};
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn request(method: Method, path: &str) -> Request {
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(method, url);
+    req.set_peer_addr(Some("203.0.113.7:4321"));
+    req
+}
+
+/// An upstream server that echoes requests back as JSON.
+fn upstream(name: &'static str) -> Server<()> {
+    let mut app = tide::new();
+    app.at("*")
+        .all(move |mut req: tide::Request<()>| async move {
+            let body = req.body_string().await?;
+            let header = |name: &str| req.header(name).map(|values| values.as_str().to_owned());
+            let echo = serde_json::json!({
+                "upstream": name,
+                "method": req.method().to_string(),
+                "path": req.url().path(),
+                "query": req.url().query(),
+                "body": body,
+                "forwarded": header("forwarded"),
+                "x-forwarded-for": header("x-forwarded-for"),
+                "x-forwarded-host": header("x-forwarded-host"),
+                "x-forwarded-proto": header("x-forwarded-proto"),
+                "connection": header("connection"),
+                "x-secret": header("x-secret"),
+                "x-kept": header("x-kept"),
+            });
+            let res = tide::Response::builder(StatusCode::Created)
+                .body(echo)
+                .header("connection", "close")
+                .header("x-upstream", name)
+                .build();
+            Ok(res)
+        });
+    app.at("/slow").get(|_| async {
+        async_std::task::sleep(Duration::from_secs(5)).await;
+        Ok("slow")
+    });
+    app
+}
+
+fn app(proxy: Proxy) -> Server<()> {
+    let mut app = tide::new();
+    app.at("/legacy/*").proxy(proxy);
+    app
+}
+
+#[async_std::test]
+async fn forwards_requests() -> tide::Result<()> {
+    let app = app(Proxy::new("http://upstream.test/app/").client(upstream("a")));
+
+    let mut req = request(Method::Post, "/legacy/users/42?page=2");
+    req.insert_header("connection", "keep-alive, x-secret");
+    req.insert_header("x-secret", "hop");
+    req.insert_header("x-kept", "end-to-end");
+    req.insert_header("x-forwarded-for", "198.51.100.1");
+    req.set_body("hello");
+    let mut res: Response = app.respond(req).await?;
+
+    assert_eq!(res.status(), StatusCode::Created);
+    assert_eq!(res["x-upstream"], "a");
+    assert!(res.header("connection").is_none());
+    let echo: serde_json::Value = res.body_json().await?;
+    assert_eq!(echo["method"], "POST");
+    assert_eq!(echo["path"], "/app/users/42");
+    assert_eq!(echo["query"], "page=2");
+    assert_eq!(echo["body"], "hello");
+    assert_eq!(echo["connection"], serde_json::Value::Null);
+    assert_eq!(echo["x-secret"], serde_json::Value::Null);
+    assert_eq!(echo["x-kept"], "end-to-end");
+    assert_eq!(
+        echo["forwarded"],
+        "for=203.0.113.7;host=example.com;proto=http"
+    );
+    assert_eq!(echo["x-forwarded-for"], "198.51.100.1, 203.0.113.7");
+    assert_eq!(echo["x-forwarded-host"], "example.com");
+    assert_eq!(echo["x-forwarded-proto"], "http");
+    Ok(())
+}
+
+#[async_std::test]
+async fn trusted_proxies() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.with(tide::security::TrustedProxies::new().trust("203.0.113.0/24"));
+    app.at("/legacy/*")
+        .proxy(Proxy::new("http://upstream.test/").client(upstream("a")));
+
+    let mut req = request(Method::Get, "/legacy/");
+    req.insert_header("x-forwarded-for", "198.51.100.1");
+    req.insert_header("x-forwarded-host", "public.example");
+    req.insert_header("x-forwarded-proto", "https");
+    let mut res: Response = app.respond(req).await?;
+
+    let echo: serde_json::Value = res.body_json().await?;
+    assert_eq!(
+        echo["forwarded"],
+        "for=198.51.100.1;host=public.example;proto=https"
+    );
+    assert_eq!(echo["x-forwarded-for"], "198.51.100.1");
+    assert_eq!(echo["x-forwarded-host"], "public.example");
+    assert_eq!(echo["x-forwarded-proto"], "https");
+    Ok(())
+}
+
+/// A client that records the URLs it is asked to send requests to.
+#[derive(Debug, Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+#[async_trait::async_trait]
+impl http_client::HttpClient for Recorder {
+    async fn send(&self, req: http::Request) -> http::Result<http::Response> {
+        self.0.lock().unwrap().push(req.url().to_string());
+        Ok(http::Response::new(StatusCode::Ok))
+    }
+}
+
+#[async_std::test]
+async fn round_robin() -> tide::Result<()> {
+    let recorder = Recorder::default();
+    let app = app(Proxy::new("http://a.test/")
+        .upstream("http://b.test/base")
+        .client(recorder.clone()));
+
+    for path in ["/legacy/", "/legacy/x", "/legacy/y?z", "/legacy"] {
+        let res: Response = app.respond(request(Method::Get, path)).await?;
+        assert_eq!(res.status(), StatusCode::Ok);
+    }
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [
+            "http://a.test/",
+            "http://b.test/base/x",
+            "http://a.test/y?z",
+            "http://b.test/base/",
+        ]
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn route_params() -> tide::Result<()> {
+    let recorder = Recorder::default();
+    let mut app = tide::new();
+    app.at("/api/:version/*")
+        .proxy(Proxy::new("http://a.test/base/").client(recorder.clone()));
+
+    for path in ["/api/v1/users/42?page=2", "/api/v2/"] {
+        let res: Response = app.respond(request(Method::Get, path)).await?;
+        assert_eq!(res.status(), StatusCode::Ok);
+    }
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        ["http://a.test/base/users/42?page=2", "http://a.test/base/"]
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn timeout() -> tide::Result<()> {
+    let app = app(Proxy::new("http://upstream.test/")
+        .client(upstream("a"))
+        .timeout(Duration::from_millis(50)));
+    let res: Response = app.respond(request(Method::Get, "/legacy/slow")).await?;
+    assert_eq!(res.status(), StatusCode::GatewayTimeout);
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Unreachable;
+
+#[async_trait::async_trait]
+impl http_client::HttpClient for Unreachable {
+    async fn send(&self, _req: http::Request) -> http::Result<http::Response> {
+        Err(http::Error::from_str(500, "connection refused"))
+    }
+}
+
+#[async_std::test]
+async fn bad_gateway() -> tide::Result<()> {
+    let app = app(Proxy::new("http://upstream.test/").client(Unreachable));
+    let res: Response = app.respond(request(Method::Get, "/legacy/")).await?;
+    assert_eq!(res.status(), StatusCode::BadGateway);
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use tide::http::headers::{
+    HeaderName, ACCEPT_RANGES, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED,
+};
+use tide::http::{Method, Request, Response, Url};
+use tide::StatusCode;
+
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+const CONTENTS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+fn request(path: &str, headers: &[(&str, &str)]) -> Request {
+    let url = Url::parse("http://example.com")
+        .unwrap()
+        .join(path)
+        .unwrap();
+    let mut req = Request::new(Method::Get, url);
+    for (name, value) in headers {
+        req.insert_header(HeaderName::from(*name), *value);
+    }
+    req
+}
+
+fn app(dir: &tempfile::TempDir) -> tide::Result<tide::Server<()>> {
+    let path = dir.path().join("file.txt");
+    std::fs::write(&path, CONTENTS)?;
+
+    let mut app = tide::new();
+    app.at("/file").serve_file(&path)?;
+    app.at("/dir/*").serve_dir(dir.path())?;
+    Ok(app)
+}
+
+#[async_std::test]
+async fn full_file() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    let mut res: Response = app.respond(request("/file", &[])).await?;
+    assert_eq!(res.status(), StatusCode::Ok);
+    assert_eq!(res[ACCEPT_RANGES], "bytes");
+    assert_eq!(res.body_string().await?, CONTENTS);
+    Ok(())
+}
+
+#[async_std::test]
+async fn single_range() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    let full: Response = app.respond(request("/file", &[])).await?;
+    for (path, range, content_range, contents) in [
+        ("/file", "bytes=0-9", "bytes 0-9/36", "0123456789"),
+        ("/dir/file.txt", "bytes=30-", "bytes 30-35/36", "uvwxyz"),
+        ("/file", "bytes=-3", "bytes 33-35/36", "xyz"),
+        ("/file", "bytes=34-100", "bytes 34-35/36", "yz"),
+    ] {
+        let mut res: Response = app.respond(request(path, &[("Range", range)])).await?;
+        assert_eq!(res.status(), StatusCode::PartialContent);
+        assert_eq!(res[CONTENT_RANGE], content_range);
+        assert_eq!(res.len(), Some(contents.len()));
+        assert_eq!(res.content_type(), full.content_type());
+        assert_eq!(res.body_string().await?, contents);
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn multiple_ranges() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    let full: Response = app.respond(request("/file", &[])).await?;
+    let mut res: Response = app
+        .respond(request("/file", &[("Range", "bytes=0-1, 10-12")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PartialContent);
+    assert!(res.header(CONTENT_RANGE).is_none());
+
+    let mime = res.content_type().unwrap();
+    assert_eq!(mime.essence(), "multipart/byteranges");
+    let boundary = mime.param("boundary").unwrap().to_string();
+    let len = res.len();
+    let body = res.body_string().await?;
+    assert_eq!(len, Some(body.len()));
+    assert_eq!(
+        body,
+        format!(
+            "--{b}\r\nContent-Type: {m}\r\nContent-Range: bytes 0-1/36\r\n\r\n01\r\n\
+             --{b}\r\nContent-Type: {m}\r\nContent-Range: bytes 10-12/36\r\n\r\nabc\r\n\
+             --{b}--\r\n",
+            b = boundary,
+            m = full.content_type().unwrap()
+        )
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn overlapping_ranges() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    let mut res: Response = app
+        .respond(request("/file", &[("Range", "bytes=0-,0-,0-,5-9")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PartialContent);
+    assert_eq!(res[CONTENT_RANGE], "bytes 0-35/36");
+    assert_eq!(res.body_string().await?, CONTENTS);
+    Ok(())
+}
+
+#[async_std::test]
+async fn unsatisfiable_range() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    let res: Response = app
+        .respond(request("/file", &[("Range", "bytes=36-")]))
+        .await?;
+    assert_eq!(res.status(), StatusCode::RequestedRangeNotSatisfiable);
+    assert_eq!(res[CONTENT_RANGE], "bytes */36");
+    Ok(())
+}
+
+#[async_std::test]
+async fn invalid_range_is_ignored() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    for range in ["bytes=5-1", "lines=0-1", "bytes=x"] {
+        let mut res: Response = app.respond(request("/file", &[("Range", range)])).await?;
+        assert_eq!(res.status(), StatusCode::Ok);
+        assert_eq!(res.body_string().await?, CONTENTS);
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn if_range() -> tide::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let app = app(&dir)?;
+    let res: Response = app.respond(request("/file", &[])).await?;
+    let etag = res[ETAG].as_str().to_owned();
+    let last_modified = res[LAST_MODIFIED].as_str().to_owned();
+    assert!(etag.starts_with("W/"));
+
+    // Neither the weak `ETag` nor the `Last-Modified` date of a file that was
+    // just written are strong validators.
+    let strong = etag.trim_start_matches("W/").to_owned();
+    for validator in [
+        "\"stale\"",
+        etag.as_str(),
+        strong.as_str(),
+        last_modified.as_str(),
+        "Thu, 01 Jan 1970 00:00:00 GMT",
+    ] {
+        let res: Response = app
+            .respond(request(
+                "/file",
+                &[("Range", "bytes=0-1"), (IF_RANGE.as_str(), validator)],
+            ))
+            .await?;
+        assert_eq!(res.status(), StatusCode::Ok);
+        assert_eq!(res.len(), Some(36));
+    }
+
+    // Once the file is older than a second its date is a strong validator.
+    let modified = SystemTime::now() - Duration::from_secs(60);
+    File::options()
+        .write(true)
+        .open(dir.path().join("file.txt"))?
+        .set_modified(modified)?;
+    let res: Response = app.respond(request("/file", &[])).await?;
+    let last_modified = res[LAST_MODIFIED].as_str().to_owned();
+    let res: Response = app
+        .respond(request(
+            "/file",
+            &[("Range", "bytes=0-1"), (IF_RANGE.as_str(), &last_modified)],
+        ))
+        .await?;
+    assert_eq!(res.status(), StatusCode::PartialContent);
+    Ok(())
+}
@@ -57,3 +57,372 @@ async fn static_endpoint_mixin() {
     assert_eq!(res.status(), 200);
     assert_eq!(res.body_string().await.unwrap().as_str(), "api");
 }
+
+fn docs_app(serve_dir: tide::fs::ServeDir) -> Server<()> {
+    let mut app = Server::new();
+    app.at("/docs/*").serve_dir_with(serve_dir);
+    app
+}
+
+fn docs_dir() -> Result<tempfile::TempDir> {
+    let tempdir = tempfile::tempdir()?;
+    fs::create_dir_all(tempdir.path().join("guide"))?;
+    fs::create_dir_all(tempdir.path().join("api"))?;
+    fs::write(tempdir.path().join("guide/index.html"), "guide")?;
+    fs::write(tempdir.path().join("api/default.htm"), "api")?;
+    fs::write(tempdir.path().join("api/a <b>.txt"), "hello")?;
+    Ok(tempdir)
+}
+
+fn get(path: &str) -> http_types::Request {
+    http_types::Request::get(
+        http_types::Url::parse("http://localhost")
+            .unwrap()
+            .join(path)
+            .unwrap(),
+    )
+}
+
+#[async_std::test]
+async fn index_file() -> Result<()> {
+    let tempdir = docs_dir()?;
+    let app = docs_app(tide::fs::ServeDir::new(tempdir.path())?);
+
+    let mut res: http::Response = app.respond(get("/docs/guide/")).await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body_string().await?, "guide");
+
+    let res: http::Response = app.respond(get("/docs/api/")).await?;
+    assert_eq!(res.status(), 404);
+
+    let app = docs_app(
+        tide::fs::ServeDir::new(tempdir.path())?.index_files(vec!["index.html", "default.htm"]),
+    );
+    let mut res: http::Response = app.respond(get("/docs/api/")).await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body_string().await?, "api");
+    Ok(())
+}
+
+#[async_std::test]
+async fn directory_redirect() -> Result<()> {
+    let tempdir = docs_dir()?;
+    let app = docs_app(tide::fs::ServeDir::new(tempdir.path())?);
+
+    let res: http::Response = app.respond(get("/docs/guide?page=2")).await?;
+    assert_eq!(res.status(), 308);
+    assert_eq!(res["location"], "guide/?page=2");
+
+    // Paths starting with `//` do not redirect to another host.
+    let tempdir = tempfile::tempdir()?;
+    fs::create_dir(tempdir.path().join("evil.example"))?;
+    let mut app = Server::new();
+    app.at("/*").serve_dir(tempdir.path())?;
+    let url = http_types::Url::parse("http://localhost//evil.example")?;
+    let res: http::Response = app.respond(http_types::Request::get(url)).await?;
+    assert_eq!(res.status(), 308);
+    assert_eq!(res["location"], "evil.example/");
+    Ok(())
+}
+
+#[async_std::test]
+async fn mount_root_redirect() -> Result<()> {
+    let tempdir = docs_dir()?;
+    let app = docs_app(tide::fs::ServeDir::new(tempdir.path())?);
+
+    let res: http::Response = app.respond(get("/docs")).await?;
+    assert_eq!(res.status(), 308);
+    assert_eq!(res["location"], "docs/");
+    Ok(())
+}
+
+#[async_std::test]
+async fn autoindex_html() -> Result<()> {
+    let tempdir = docs_dir()?;
+    let app = docs_app(tide::fs::ServeDir::new(tempdir.path())?.autoindex(true));
+
+    let mut res: http::Response = app.respond(get("/docs/api/")).await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.content_type(), Some(http::mime::HTML));
+    let body = res.body_string().await?;
+    assert!(body.contains("<title>Index of /docs/api/</title>"));
+    assert!(body.contains(r#"<a href="../">../</a>"#));
+    assert!(body.contains(r#"<a href="a%20%3Cb%3E.txt">a &lt;b&gt;.txt</a></td><td>5</td>"#));
+    assert!(body.contains(r#"<a href="default.htm">default.htm</a>"#));
+
+    let mut res: http::Response = app.respond(get("/docs/")).await?;
+    let body = res.body_string().await?;
+    assert!(!body.contains("../"));
+    assert!(body.find("api/").unwrap() < body.find("guide/").unwrap());
+
+    // Index files take precedence over listings.
+    let mut res: http::Response = app.respond(get("/docs/guide/")).await?;
+    assert_eq!(res.body_string().await?, "guide");
+    Ok(())
+}
+
+#[async_std::test]
+async fn autoindex_json() -> Result<()> {
+    let tempdir = docs_dir()?;
+    let app = docs_app(tide::fs::ServeDir::new(tempdir.path())?.autoindex(true));
+
+    let mut req = get("/docs/");
+    req.insert_header("accept", "application/json, text/html;q=0.9");
+    let mut res: http::Response = app.respond(req).await?;
+    assert_eq!(res.content_type(), Some(http::mime::JSON));
+    let entries: serde_json::Value = res.body_json().await?;
+    assert_eq!(entries[0]["name"], "api");
+    assert_eq!(entries[0]["type"], "directory");
+    assert_eq!(entries[0]["size"], serde_json::Value::Null);
+    assert_eq!(entries[1]["name"], "guide");
+    assert!(entries[1]["modified"].as_str().unwrap().ends_with("GMT"));
+    Ok(())
+}
+
+fn spa_app() -> Result<(tempfile::TempDir, Server<()>)> {
+    let tempdir = tempfile::tempdir()?;
+    fs::create_dir_all(tempdir.path().join("assets"))?;
+    fs::write(tempdir.path().join("index.html"), "<html>app</html>")?;
+    fs::write(tempdir.path().join("assets/app.js"), "console.log(1)")?;
+
+    let mut app = Server::new();
+    app.at("/app/*").serve_spa(tempdir.path(), "index.html")?;
+    app.at("/custom/*").serve_dir_with(
+        tide::fs::ServeDir::new(tempdir.path())?
+            .fallback("index.html")
+            .asset_extensions(vec!["JS"]),
+    );
+    Ok((tempdir, app))
+}
+
+#[async_std::test]
+async fn spa_fallback() -> Result<()> {
+    let (_tempdir, app) = spa_app()?;
+
+    let mut res: http::Response = app.respond(get("/app/assets/app.js")).await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body_string().await?, "console.log(1)");
+
+    for path in [
+        "/app/",
+        "/app/users/42",
+        "/app/users/john.doe",
+        "/app/assets/",
+    ] {
+        let mut res: http::Response = app.respond(get(path)).await?;
+        assert_eq!(res.status(), 200, "{}", path);
+        assert_eq!(res.body_string().await?, "<html>app</html>");
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn spa_fallback_skips_assets() -> Result<()> {
+    let (_tempdir, app) = spa_app()?;
+
+    for path in [
+        "/app/assets/missing.js",
+        "/app/logo.PNG",
+        "/custom/missing.js",
+    ] {
+        let res: http::Response = app.respond(get(path)).await?;
+        assert_eq!(res.status(), 404, "{}", path);
+    }
+
+    let res: http::Response = app.respond(get("/custom/logo.png")).await?;
+    assert_eq!(res.status(), 200);
+    Ok(())
+}
+
+#[async_std::test]
+async fn cache_control_rules() -> Result<()> {
+    use http::cache::CacheDirective;
+    use std::time::Duration;
+
+    let tempdir = tempfile::tempdir()?;
+    fs::create_dir_all(tempdir.path().join("assets"))?;
+    fs::write(tempdir.path().join("index.html"), "<html>app</html>")?;
+    fs::write(tempdir.path().join("assets/app.3f2a.js"), "console.log(1)")?;
+    fs::write(tempdir.path().join("robots.txt"), "")?;
+    fs::create_dir_all(tempdir.path().join("docs"))?;
+    fs::write(tempdir.path().join("sw.js"), "")?;
+    fs::write(tempdir.path().join("docs/sw.js"), "")?;
+
+    let mut app = Server::new();
+    app.at("/app/*").serve_dir_with(
+        tide::fs::ServeDir::new(tempdir.path())?
+            .cache_control(
+                "assets/**",
+                vec![
+                    CacheDirective::Public,
+                    CacheDirective::MaxAge(Duration::from_secs(31_536_000)),
+                    CacheDirective::Immutable,
+                ],
+            )
+            .cache_control("*.html", vec![CacheDirective::NoCache])
+            .cache_control("/sw.js", vec![CacheDirective::NoStore])
+            .fallback("index.html")
+            .expires(true),
+    );
+
+    let res: http::Response = app.respond(get("/app/assets/app.3f2a.js")).await?;
+    assert_eq!(res["cache-control"], "public, max-age=31536000, immutable");
+    assert!(res.header("expires").is_some());
+    assert!(res.header("last-modified").is_some());
+    let etag = res["etag"].as_str().to_owned();
+
+    for path in ["/app/", "/app/users/42"] {
+        let res: http::Response = app.respond(get(path)).await?;
+        assert_eq!(res["cache-control"], "no-cache", "{}", path);
+        assert!(res.header("expires").is_none());
+    }
+
+    let res: http::Response = app.respond(get("/app/robots.txt")).await?;
+    assert!(res.header("cache-control").is_none());
+
+    // A leading `/` anchors a pattern at the root of the directory.
+    let res: http::Response = app.respond(get("/app/sw.js")).await?;
+    assert_eq!(res["cache-control"], "no-store");
+    let res: http::Response = app.respond(get("/app/docs/sw.js")).await?;
+    assert!(res.header("cache-control").is_none());
+
+    let res: http::Response = app.respond(get("/app/assets/missing.js")).await?;
+    assert_eq!(res.status(), 404);
+    assert!(res.header("cache-control").is_none());
+
+    // Revalidations keep the caching headers.
+    let mut req = get("/app/assets/app.3f2a.js");
+    req.insert_header("if-none-match", etag);
+    let res: http::Response = app.respond(req).await?;
+    assert_eq!(res.status(), 304);
+    assert_eq!(res["cache-control"], "public, max-age=31536000, immutable");
+    Ok(())
+}
+
+fn private_dir() -> Result<tempfile::TempDir> {
+    let tempdir = tempfile::tempdir()?;
+    fs::create_dir_all(tempdir.path().join(".git"))?;
+    fs::create_dir_all(tempdir.path().join(".well-known"))?;
+    fs::create_dir_all(tempdir.path().join("drafts"))?;
+    fs::write(tempdir.path().join(".env"), "SECRET=1")?;
+    fs::write(tempdir.path().join(".git/config"), "[core]")?;
+    fs::write(tempdir.path().join(".well-known/security.txt"), "contact")?;
+    fs::write(tempdir.path().join("drafts/post.html"), "draft")?;
+    fs::write(tempdir.path().join("app.js.map"), "{}")?;
+    fs::write(tempdir.path().join("index.html"), "home")?;
+    Ok(tempdir)
+}
+
+#[async_std::test]
+async fn hidden_files() -> Result<()> {
+    let tempdir = private_dir()?;
+    let app = docs_app(tide::fs::ServeDir::new(tempdir.path())?.autoindex(true));
+
+    for path in [
+        "/docs/.env",
+        "/docs/.git/config",
+        "/docs/.git/",
+        "/docs/.well-known/security.txt",
+    ] {
+        let res: http::Response = app.respond(get(path)).await?;
+        assert_eq!(res.status(), 404, "{}", path);
+    }
+
+    let app = docs_app(
+        tide::fs::ServeDir::new(tempdir.path())?
+            .hidden_files(true)
+            .index_files(Vec::<String>::new())
+            .autoindex(true),
+    );
+    let mut res: http::Response = app.respond(get("/docs/.env")).await?;
+    assert_eq!(res.body_string().await?, "SECRET=1");
+    let mut res: http::Response = app.respond(get("/docs/")).await?;
+    assert!(res.body_string().await?.contains(".git/"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn allow_and_deny() -> Result<()> {
+    let tempdir = private_dir()?;
+    let app = docs_app(
+        tide::fs::ServeDir::new(tempdir.path())?
+            .allow("/.well-known")
+            .deny("/drafts")
+            .deny("*.map")
+            .index_files(Vec::<String>::new())
+            .autoindex(true),
+    );
+
+    let mut res: http::Response = app.respond(get("/docs/.well-known/security.txt")).await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body_string().await?, "contact");
+
+    for path in [
+        "/docs/drafts/post.html",
+        "/docs/drafts/",
+        "/docs/app.js.map",
+        "/docs/.env",
+    ] {
+        let res: http::Response = app.respond(get(path)).await?;
+        assert_eq!(res.status(), 404, "{}", path);
+    }
+
+    let mut res: http::Response = app.respond(get("/docs/")).await?;
+    let body = res.body_string().await?;
+    assert!(body.contains(".well-known/"));
+    assert!(body.contains("index.html"));
+    for hidden in [".env", ".git", "drafts", "app.js.map"] {
+        assert!(!body.contains(hidden), "{}", hidden);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[async_std::test]
+async fn symlinks() -> Result<()> {
+    use std::os::unix::fs::symlink;
+    use tide::fs::{DiskFs, ServeDir, Symlinks};
+
+    let outside = tempfile::tempdir()?;
+    fs::write(outside.path().join("secret.txt"), "secret")?;
+    let tempdir = tempfile::tempdir()?;
+    fs::write(tempdir.path().join("file.txt"), "file")?;
+    symlink(outside.path(), tempdir.path().join("outside"))?;
+    symlink(
+        tempdir.path().join("file.txt"),
+        tempdir.path().join("link.txt"),
+    )?;
+
+    let app = docs_app(ServeDir::new(tempdir.path())?.autoindex(true));
+    let res: http::Response = app.respond(get("/docs/outside/secret.txt")).await?;
+    assert_eq!(res.status(), 404);
+    let mut res: http::Response = app.respond(get("/docs/link.txt")).await?;
+    assert_eq!(res.body_string().await?, "file");
+    let mut res: http::Response = app.respond(get("/docs/")).await?;
+    let body = res.body_string().await?;
+    assert!(body.contains("link.txt"));
+    assert!(!body.contains("outside"));
+
+    // Symlinks to hidden files are hidden as well.
+    fs::write(tempdir.path().join(".env"), "secret")?;
+    symlink(tempdir.path().join(".env"), tempdir.path().join("config"))?;
+    let fs = DiskFs::new(tempdir.path())?;
+    for symlinks in [Symlinks::WithinRoot, Symlinks::Follow] {
+        let app = docs_app(ServeDir::from_fs(fs.clone().symlinks(symlinks)).autoindex(true));
+        let res: http::Response = app.respond(get("/docs/config")).await?;
+        assert_eq!(res.status(), 404);
+        let mut res: http::Response = app.respond(get("/docs/")).await?;
+        assert!(!res.body_string().await?.contains("config"));
+    }
+
+    let fs = DiskFs::new(tempdir.path())?.symlinks(Symlinks::Deny);
+    let app = docs_app(ServeDir::from_fs(fs));
+    let res: http::Response = app.respond(get("/docs/link.txt")).await?;
+    assert_eq!(res.status(), 404);
+
+    let fs = DiskFs::new(tempdir.path())?.symlinks(Symlinks::Follow);
+    let app = docs_app(ServeDir::from_fs(fs));
+    let mut res: http::Response = app.respond(get("/docs/outside/secret.txt")).await?;
+    assert_eq!(res.body_string().await?, "secret");
+    Ok(())
+}
@@ -4,6 +4,7 @@ use async_std::task;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use tide::listener::Shutdown;
 use tide::{Body, Request};
 
 #[test]
@@ -99,3 +100,262 @@ fn json() -> tide::Result<()> {
         server.race(client).await
     })
 }
+
+#[test]
+fn graceful_shutdown() -> tide::Result<()> {
+    task::block_on(async {
+        let port = test_utils::find_port().await;
+        let shutdown = Shutdown::new();
+        let handle = shutdown.clone();
+
+        let server = task::spawn(async move {
+            let mut app = tide::new();
+            app.at("/").get(move |_| {
+                let handle = handle.clone();
+                async move {
+                    handle.trigger();
+                    task::sleep(Duration::from_millis(100)).await;
+                    Ok("goodbye")
+                }
+            });
+            app.listen_with_shutdown(("localhost", port), shutdown)
+                .await?;
+            Result::<(), http_types::Error>::Ok(())
+        });
+
+        task::sleep(Duration::from_millis(100)).await;
+        let mut res = surf::get(format!("http://localhost:{}", port))
+            .await
+            .unwrap();
+        assert_eq!(res.body_string().await.unwrap(), "goodbye");
+        assert_eq!(res["connection"], "close");
+
+        server
+            .timeout(Duration::from_secs(1))
+            .await
+            .expect("server did not shut down")?;
+        assert!(surf::get(format!("http://localhost:{}", port))
+            .await
+            .is_err());
+        Ok(())
+    })
+}
+
+#[test]
+fn shutdown_drain_timeout() -> tide::Result<()> {
+    task::block_on(async {
+        let port = test_utils::find_port().await;
+        let shutdown = Shutdown::new().with_drain_timeout(Duration::from_millis(100));
+        let handle = shutdown.clone();
+
+        let server = task::spawn(async move {
+            let mut app = tide::new();
+            app.at("/").get(|_| async {
+                task::sleep(Duration::from_secs(10)).await;
+                Ok("too late")
+            });
+            app.listen_with_shutdown(("localhost", port), shutdown)
+                .await?;
+            Result::<(), http_types::Error>::Ok(())
+        });
+
+        let client = task::spawn(async move {
+            task::sleep(Duration::from_millis(100)).await;
+            surf::get(format!("http://localhost:{}", port)).await
+        });
+
+        task::sleep(Duration::from_millis(200)).await;
+        handle.trigger();
+        server
+            .timeout(Duration::from_secs(1))
+            .await
+            .expect("server did not shut down")?;
+        assert!(client.await.is_err());
+        Ok(())
+    })
+}
+
+#[test]
+fn shutdown_silent_connection() -> tide::Result<()> {
+    task::block_on(async {
+        let port = test_utils::find_port().await;
+        let shutdown = Shutdown::new();
+        let handle = shutdown.clone();
+
+        let server = task::spawn(async move {
+            let app = tide::new();
+            app.listen_with_shutdown(("localhost", port), shutdown)
+                .await?;
+            Result::<(), http_types::Error>::Ok(())
+        });
+
+        // A connection that never sends a byte is idle as well.
+        task::sleep(Duration::from_millis(100)).await;
+        let mut stream = async_std::net::TcpStream::connect(("localhost", port)).await?;
+        task::sleep(Duration::from_millis(100)).await;
+        handle.trigger();
+        server
+            .timeout(Duration::from_secs(5))
+            .await
+            .expect("server did not shut down")?;
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).await?;
+        assert!(rest.is_empty());
+        Ok(())
+    })
+}
+
+#[async_std::test]
+async fn shutdown_listener_groups() -> tide::Result<()> {
+    use async_std::net::TcpStream;
+    use tide::listener::{ConcurrentListener, FailoverListener, Listener};
+
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("hello") });
+
+    let mut concurrent = ConcurrentListener::new();
+    concurrent.add("127.0.0.1:0")?;
+    concurrent.add("127.0.0.1:0")?;
+    let mut failover = FailoverListener::new();
+    failover.add("127.0.0.1:0")?;
+    let listeners: Vec<Box<dyn Listener<()>>> = vec![Box::new(concurrent), Box::new(failover)];
+
+    for mut listener in listeners {
+        let shutdown = Shutdown::new();
+        listener.set_shutdown(shutdown.clone());
+        listener.bind(app.clone()).await?;
+        let addrs = listener.local_addrs();
+        let server = task::spawn(async move { listener.accept().await });
+
+        // Leave a kept-alive connection idle on every address.
+        let mut streams = Vec::new();
+        for addr in &addrs {
+            let mut stream = TcpStream::connect(addr.tcp().unwrap()).await?;
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .await?;
+            let mut res = String::new();
+            let mut buf = [0; 1024];
+            while !res.ends_with("hello") {
+                let len = stream.read(&mut buf).await?;
+                assert!(len > 0, "unexpected end of response: {}", res);
+                res.push_str(std::str::from_utf8(&buf[..len])?);
+            }
+            streams.push(stream);
+        }
+
+        // Idle connections are closed rather than holding up the drain.
+        shutdown.trigger();
+        server
+            .timeout(Duration::from_secs(5))
+            .await
+            .expect("server did not shut down")?;
+        for mut stream in streams {
+            let mut rest = Vec::new();
+            stream.read_to_end(&mut rest).await?;
+            assert!(rest.is_empty());
+        }
+        for addr in &addrs {
+            assert!(TcpStream::connect(addr.tcp().unwrap()).await.is_err());
+        }
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn serve_connection() -> tide::Result<()> {
+    use tide::listener::ConnectionInfo;
+
+    let mut app = tide::new();
+    app.at("/hello").get(|req: Request<()>| async move {
+        Ok(format!(
+            "{} {} {}",
+            req.url(),
+            req.peer_addr().unwrap_or("none"),
+            req.local_addr().unwrap_or("none")
+        ))
+    });
+    let shutdown = Shutdown::new();
+    let handle = shutdown.clone();
+    app.at("/stop").get(move |_| {
+        let handle = handle.clone();
+        async move {
+            handle.trigger();
+            Ok("stopping")
+        }
+    });
+
+    let info = ConnectionInfo::new()
+        .peer_addr("pipe:client")
+        .local_addr("pipe:server")
+        .tls(true)
+        .shutdown(shutdown.clone());
+    let (mut client, stream) = tide::testing::duplex();
+    let server = task::spawn(async move { app.serve_connection(stream, info).await });
+
+    let request = b"GET /hello HTTP/1.1\r\nHost: example.com\r\n\r\n";
+    client.write_all(request).await?;
+    let mut res = String::new();
+    let mut buf = [0; 1024];
+    while !res.ends_with("pipe:server") {
+        let len = client.read(&mut buf).await?;
+        assert!(len > 0, "unexpected end of response: {}", res);
+        res.push_str(std::str::from_utf8(&buf[..len])?);
+    }
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(res.ends_with("https://example.com/hello pipe:client pipe:server"));
+    assert!(!res.contains("connection: close"));
+
+    // Once shutdown is triggered the client is asked to close the connection.
+    client
+        .write_all(b"GET /stop HTTP/1.1\r\nHost: example.com\r\n\r\n")
+        .await?;
+    let mut res = String::new();
+    client.read_to_string(&mut res).await?;
+    assert!(res.contains("connection: close"));
+    server.await?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn local_addrs() -> tide::Result<()> {
+    use async_std::net::TcpStream;
+    use tide::listener::{ConcurrentListener, FailoverListener, Listener};
+
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("hello") });
+
+    let listener = app.clone().bind("127.0.0.1:0").await?;
+    let addrs = listener.local_addrs();
+    assert_eq!(addrs.len(), 1);
+    let addr = addrs[0].tcp().unwrap();
+    assert_ne!(addr.port(), 0);
+    assert_eq!(listener.info()[0].local_addr(), Some(&addrs[0]));
+
+    let mut listener = listener;
+    task::spawn(async move { listener.accept().await });
+    let mut stream = TcpStream::connect(addr).await?;
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await?;
+    let mut res = String::new();
+    stream.read_to_string(&mut res).await?;
+    assert!(res.ends_with("hello"));
+
+    let mut concurrent = ConcurrentListener::new();
+    concurrent.add("127.0.0.1:0")?;
+    concurrent.add("127.0.0.1:0")?;
+    let concurrent = app.clone().bind(concurrent).await?;
+    let addrs = concurrent.local_addrs();
+    assert_eq!(addrs.len(), 2);
+    assert_ne!(addrs[0], addrs[1]);
+
+    let mut failover = FailoverListener::new();
+    failover.add(addr)?;
+    failover.add("127.0.0.1:0")?;
+    let failover = app.bind(failover).await?;
+    let addrs = failover.local_addrs();
+    assert_eq!(addrs.len(), 1);
+    assert_ne!(addrs[0].tcp(), Some(addr));
+    Ok(())
+}
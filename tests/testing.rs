@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use tide::testing::TestClient;
+use tide::{Body, Request, StatusCode};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Cat {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct Page {
+    page: u32,
+}
+
+fn app() -> tide::Server<()> {
+    let mut app = tide::new();
+    app.at("/echo").all(|mut req: Request<()>| async move {
+        let body = req.body_string().await?;
+        let res = tide::Response::builder(StatusCode::Ok)
+            .header("x-method", req.method().to_string())
+            .header("x-query", req.url().query().unwrap_or_default())
+            .body(body)
+            .build();
+        Ok(res)
+    });
+    app.at("/cats").post(|mut req: Request<()>| async move {
+        let cat: Cat = req.body_json().await?;
+        Body::from_json(&cat)
+    });
+    app
+}
+
+#[async_std::test]
+async fn methods() -> tide::Result<()> {
+    let client = TestClient::new(app());
+    let requests = [
+        (client.delete("/echo"), "DELETE"),
+        (client.get("/echo"), "GET"),
+        (client.options("/echo"), "OPTIONS"),
+        (client.patch("/echo"), "PATCH"),
+        (client.post("/echo"), "POST"),
+        (client.put("/echo"), "PUT"),
+        (client.trace("/echo"), "TRACE"),
+    ];
+    for (req, method) in requests {
+        let res = req.await?;
+        res.assert_status(StatusCode::Ok)
+            .assert_header("x-method", method);
+    }
+    client.head("/echo").await?.assert_status(200);
+    Ok(())
+}
+
+#[async_std::test]
+async fn bodies() -> tide::Result<()> {
+    let client = TestClient::new(app());
+
+    let mut res = client
+        .put("/echo")
+        .query(&Page { page: 2 })
+        .header("x-ignored", "yes")
+        .body("hello")
+        .await?;
+    res.assert_header("x-query", "page=2");
+    res.assert_body("hello").await;
+
+    let mut res = client
+        .post("/cats")
+        .body_json(&Cat {
+            name: "chashu".into(),
+        })
+        .await?;
+    res.assert_status(200)
+        .assert_header("content-type", "application/json");
+    res.assert_json(&serde_json::json!({ "name": "chashu" }))
+        .await;
+
+    let mut res = client
+        .post("/echo")
+        .body_form(&Cat {
+            name: "mr mittens".into(),
+        })
+        .await?;
+    res.assert_body("name=mr+mittens").await;
+    Ok(())
+}
+
+#[cfg(feature = "h1-server")]
+#[async_std::test]
+async fn h1() -> tide::Result<()> {
+    let mut app = app();
+    app.at("/stream").get(|_| async {
+        let reader = async_std::io::Cursor::new(vec![b'x'; 100_000]);
+        Ok(Body::from_reader(reader, None))
+    });
+    let client = TestClient::new(app).h1(true);
+
+    let mut res = client.post("/echo").body("over the wire").await?;
+    res.assert_status(200).assert_header("x-method", "POST");
+    res.assert_body("over the wire").await;
+
+    let mut res = client.get("/stream").await?;
+    res.assert_header("transfer-encoding", "chunked");
+    assert_eq!(res.body_bytes().await?.len(), 100_000);
+
+    client.get("/missing").await?.assert_status(404);
+    Ok(())
+}
+
+#[async_std::test]
+#[should_panic(expected = "expected status 201, got 200")]
+async fn failed_assertion() {
+    let client = TestClient::new(app());
+    client.get("/echo").await.unwrap().assert_status(201);
+}
+
+#[cfg(feature = "cookies")]
+#[async_std::test]
+async fn cookies() -> tide::Result<()> {
+    use tide::http::cookies::Cookie;
+
+    let mut app = tide::new();
+    app.at("/login").post(|_| async {
+        let mut res = tide::Response::new(StatusCode::Ok);
+        res.insert_cookie(Cookie::new("session", "s3cr3t value"));
+        Ok(res)
+    });
+    app.at("/logout").post(|_| async {
+        let mut res = tide::Response::new(StatusCode::Ok);
+        res.remove_cookie(Cookie::named("session"));
+        Ok(res)
+    });
+    app.at("/whoami").get(|req: Request<()>| async move {
+        Ok(req
+            .cookie("session")
+            .map(|cookie| cookie.value().to_owned())
+            .unwrap_or_default())
+    });
+
+    let client = TestClient::new(app);
+    client.post("/login").await?;
+    assert_eq!(client.cookie("session").unwrap().value(), "s3cr3t value");
+    client
+        .get("/whoami")
+        .await?
+        .assert_body("s3cr3t value")
+        .await;
+
+    client.clone().post("/logout").await?;
+    assert!(client.cookie("session").is_none());
+    client.get("/whoami").await?.assert_body("").await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn addresses() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/").get(|req: Request<()>| async move {
+        Ok(format!(
+            "{} -> {}",
+            req.peer_addr().unwrap_or("none"),
+            req.local_addr().unwrap_or("none")
+        ))
+    });
+
+    let client = TestClient::new(app.clone());
+    client.get("/").await?.assert_body("none -> none").await;
+
+    let client = client.peer_addr("192.0.2.1:4321").local_addr("[::1]:8080");
+    client
+        .get("/")
+        .await?
+        .assert_body("192.0.2.1:4321 -> [::1]:8080")
+        .await;
+
+    let mut req = tide::http::Request::get("http://example.com/");
+    req.set_peer_addr(Some("198.51.100.7:1"));
+    client
+        .send(req)
+        .await?
+        .assert_body("198.51.100.7:1 -> [::1]:8080")
+        .await;
+
+    #[cfg(feature = "h1-server")]
+    {
+        let client = client.h1(true);
+        client
+            .get("/")
+            .await?
+            .assert_body("192.0.2.1:4321 -> [::1]:8080")
+            .await;
+
+        let mut req = tide::http::Request::get("http://example.com/");
+        req.set_peer_addr(Some("198.51.100.7:1"));
+        client
+            .send(req)
+            .await?
+            .assert_body("198.51.100.7:1 -> [::1]:8080")
+            .await;
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn http_client() -> tide::Result<()> {
+    use std::convert::TryInto;
+    use tide::http::Url;
+
+    let mut app = tide::new();
+    app.at("/upload").post(|mut req: Request<()>| async move {
+        let peer_addr = req.peer_addr().unwrap_or("none").to_owned();
+        let len = req.len();
+        let mut res = tide::Response::new(StatusCode::Ok);
+        res.insert_header("x-peer", peer_addr);
+        res.insert_header("x-len", format!("{:?}", len));
+        res.set_body(req.take_body());
+        Ok(res)
+    });
+
+    let data = vec![b'x'; 100_000];
+    let clients: Vec<surf::Client> = vec![
+        surf::Config::new()
+            .set_http_client(app.clone())
+            .set_base_url(Url::parse("http://example.com")?)
+            .try_into()?,
+        surf::Config::new()
+            .set_http_client(TestClient::new(app).peer_addr("192.0.2.1:4321"))
+            .set_base_url(Url::parse("http://example.com")?)
+            .try_into()?,
+    ];
+    for (client, peer) in clients.iter().zip(["none", "192.0.2.1:4321"]) {
+        let body = Body::from_reader(async_std::io::Cursor::new(data.clone()), None);
+        let mut res = client.post("/upload").body(body).await?;
+        assert_eq!(res["x-peer"], peer);
+        assert_eq!(res["x-len"], "None");
+        assert_eq!(res.body_bytes().await?, data);
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+mod test_utils;
+use async_std::net::TcpStream;
+use async_std::prelude::*;
+use async_std::task;
+use std::convert::TryFrom;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_rustls::rustls::pki_types::ServerName;
+use futures_rustls::rustls::{ClientConfig, RootCertStore};
+use futures_rustls::TlsConnector;
+use http_types::{Method, Request, Url};
+use tide::listener::{ConnectionOptions, Listener, TlsListener};
+
+const CERT: &str = "tests/fixtures/tls/cert.pem";
+const KEY: &str = "tests/fixtures/tls/key.pem";
+
+fn connector() -> TlsConnector {
+    let mut roots = RootCertStore::empty();
+    let cert = std::fs::read(CERT).unwrap();
+    for cert in rustls_pemfile::certs(&mut cert.as_slice()) {
+        roots.add(cert.unwrap()).unwrap();
+    }
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    TlsConnector::from(Arc::new(config))
+}
+
+async fn get(port: u16, path: &str) -> http_types::Result<http_types::Response> {
+    let stream = TcpStream::connect(("localhost", port)).await?;
+    let domain = ServerName::try_from("localhost").unwrap();
+    let stream = connector().connect(domain, stream).await?;
+    let url = Url::parse(&format!("https://localhost:{}{}", port, path))?;
+    async_h1::connect(stream, Request::new(Method::Get, url)).await
+}
+
+#[async_std::test]
+async fn tls_listener() -> tide::Result<()> {
+    let port = test_utils::find_port().await;
+    let mut app = tide::new();
+    app.at("/")
+        .get(|req: tide::Request<()>| async move { Ok(req.url().scheme().to_owned()) });
+
+    let mut listener = app
+        .bind(
+            TlsListener::build()
+                .addrs(("localhost", port))
+                .cert(CERT)
+                .key(KEY),
+        )
+        .await?;
+    let info = listener.info();
+    assert!(info[0].is_encrypted());
+    assert!(info[0].connection().starts_with("https://"));
+
+    let server = task::spawn(async move {
+        listener.accept().await?;
+        tide::Result::Ok(())
+    });
+
+    let client = async {
+        task::sleep(Duration::from_millis(100)).await;
+        let mut res = get(port, "/").await?;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body_string().await?, "https");
+        Ok(())
+    };
+
+    server.race(client).await
+}
+
+#[async_std::test]
+async fn tls_listener_from_url() -> tide::Result<()> {
+    let port = test_utils::find_port().await;
+    let server = task::spawn(async move {
+        let mut app = tide::new();
+        app.at("/").get(|_| async { Ok("hello tls") });
+        app.listen(format!(
+            "https://localhost:{}?cert={}&key={}",
+            port, CERT, KEY
+        ))
+        .await?;
+        tide::Result::Ok(())
+    });
+
+    let client = async {
+        task::sleep(Duration::from_millis(100)).await;
+        let mut res = get(port, "/").await?;
+        assert_eq!(res.body_string().await?, "hello tls");
+        Ok(())
+    };
+
+    server.race(client).await
+}
+
+#[async_std::test]
+async fn tls_listener_options() -> tide::Result<()> {
+    let port = test_utils::find_port().await;
+    let mut app = tide::new();
+    app.at("/").get(|_| async { Ok("hello tls") });
+
+    let mut listener = app
+        .bind(
+            TlsListener::build()
+                .addrs(("localhost", port))
+                .cert(CERT)
+                .key(KEY)
+                .options(ConnectionOptions::new().keep_alive(false))
+                .handshake_timeout(Duration::from_millis(100)),
+        )
+        .await?;
+    let server = task::spawn(async move {
+        listener.accept().await?;
+        tide::Result::Ok(())
+    });
+
+    let client = async {
+        task::sleep(Duration::from_millis(100)).await;
+        let res = get(port, "/").await?;
+        assert_eq!(res["connection"], "close");
+
+        // Clients that do not complete the handshake in time are dropped.
+        let mut stream = TcpStream::connect(("localhost", port)).await?;
+        let mut rest = Vec::new();
+        stream
+            .read_to_end(&mut rest)
+            .timeout(Duration::from_secs(5))
+            .await??;
+        assert!(rest.is_empty());
+        Ok(())
+    };
+
+    server.race(client).await
+}
+
+#[async_std::test]
+async fn tls_listener_missing_key() {
+    let app = tide::new();
+    let err = app
+        .bind(TlsListener::build().addrs("localhost:0").cert(CERT))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "TlsListener requires both a cert and a key"
+    );
+}
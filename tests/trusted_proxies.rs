@@ -0,0 +1,100 @@
+use tide::http::{Method, Request, Response, Url};
+use tide::security::TrustedProxies;
+use tide::Server;
+
+fn app(trusted_proxies: Option<TrustedProxies>) -> Server<()> {
+    let mut app = tide::new();
+    if let Some(trusted_proxies) = trusted_proxies {
+        app.with(trusted_proxies);
+    }
+    app.at("/").get(|req: tide::Request<()>| async move {
+        Ok(serde_json::json!({
+            "remote": req.remote(),
+            "host": req.host(),
+            "url": req.url().as_str(),
+        }))
+    });
+    app
+}
+
+fn request(peer_addr: &str, headers: &[(&str, &str)]) -> Request {
+    let mut req = Request::new(Method::Get, Url::parse("http://internal:8080/").unwrap());
+    req.set_peer_addr(Some(peer_addr));
+    for (name, value) in headers {
+        req.append_header(*name, *value);
+    }
+    req
+}
+
+async fn client_of(app: &Server<()>, req: Request) -> tide::Result<serde_json::Value> {
+    let mut res: Response = app.respond(req).await?;
+    res.body_json().await
+}
+
+#[async_std::test]
+async fn untrusted_headers_are_ignored() -> tide::Result<()> {
+    let headers = [
+        (
+            "forwarded",
+            "for=198.51.100.1;host=evil.example;proto=https",
+        ),
+        ("x-forwarded-for", "198.51.100.1"),
+    ];
+    for app in [
+        app(None),
+        app(Some(TrustedProxies::new().trust("10.0.0.0/8"))),
+    ] {
+        let client = client_of(&app, request("203.0.113.7:4321", &headers)).await?;
+        assert_eq!(client["remote"], "203.0.113.7:4321");
+        assert_eq!(client["host"], "internal");
+        assert_eq!(client["url"], "http://internal:8080/");
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn x_forwarded_headers() -> tide::Result<()> {
+    let app = app(Some(TrustedProxies::new().trust("10.0.0.0/8")));
+    let req = request(
+        "10.0.0.2:4321",
+        &[
+            ("x-forwarded-for", "192.0.2.1, 198.51.100.1, 10.0.0.5"),
+            ("x-forwarded-host", "example.org:8443"),
+            ("x-forwarded-proto", "https"),
+        ],
+    );
+    let client = client_of(&app, req).await?;
+    assert_eq!(client["remote"], "198.51.100.1");
+    assert_eq!(client["host"], "example.org:8443");
+    assert_eq!(client["url"], "https://example.org:8443/");
+    Ok(())
+}
+
+#[async_std::test]
+async fn forwarded_header() -> tide::Result<()> {
+    let app = app(Some(TrustedProxies::new().trust("10.0.0.0/8").trust("::1")));
+    let req = request(
+        "[::1]:4321",
+        &[
+            ("forwarded", "for=192.0.2.1;host=spoofed.example"),
+            (
+                "forwarded",
+                r#"for="[2001:db8::17]:4711";host=example.org;proto=https, for=10.0.0.9"#,
+            ),
+        ],
+    );
+    let client = client_of(&app, req).await?;
+    assert_eq!(client["remote"], "2001:db8::17");
+    assert_eq!(client["host"], "example.org");
+    assert_eq!(client["url"], "https://example.org/");
+
+    // When every hop is trusted, the farthest one is the client.
+    let req = request(
+        "10.0.0.2:4321",
+        &[("forwarded", "for=10.0.0.3, for=10.0.0.4")],
+    );
+    let client = client_of(&app, req).await?;
+    assert_eq!(client["remote"], "10.0.0.3");
+    assert_eq!(client["url"], "http://internal:8080/");
+    Ok(())
+}
@@ -43,4 +43,15 @@ mod unix_tests {
             server.race(client).await
         })
     }
+
+    #[async_std::test]
+    async fn local_addrs() -> Result<(), http_types::Error> {
+        use tide::listener::{ListenAddr, Listener};
+
+        let tmp_dir = tempdir()?;
+        let sock_path = tmp_dir.path().join("sock");
+        let listener = tide::new().bind(sock_path.clone()).await?;
+        assert_eq!(listener.local_addrs(), [ListenAddr::Unix(sock_path)]);
+        Ok(())
+    }
 }
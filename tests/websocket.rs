@@ -0,0 +1,174 @@
+mod test_utils;
+use async_std::net::TcpStream;
+use async_std::prelude::*;
+use async_std::task;
+use std::time::Duration;
+
+use async_tungstenite::tungstenite::client::IntoClientRequest;
+use async_tungstenite::tungstenite::http::HeaderValue;
+use futures_util::SinkExt;
+use tide::websocket::{Message, WebSocket};
+
+fn echo_app() -> tide::Server<()> {
+    let mut app = tide::new();
+    app.at("/echo").get(
+        WebSocket::new(|_req, mut conn| async move {
+            if let Some(protocol) = conn.protocol().map(String::from) {
+                conn.send_string(protocol).await?;
+            }
+            while let Some(message) = conn.next().await {
+                match message? {
+                    Message::Text(text) => conn.send_string(text).await?,
+                    Message::Binary(bytes) => conn.send_bytes(bytes).await?,
+                    _ => {}
+                }
+            }
+            Ok(())
+        })
+        .with_protocols(&["chat", "superchat"]),
+    );
+    app
+}
+
+#[test]
+fn websocket_echo() -> tide::Result<()> {
+    task::block_on(async {
+        let port = test_utils::find_port().await;
+        let server = task::spawn(async move {
+            echo_app().listen(("localhost", port)).await?;
+            tide::Result::Ok(())
+        });
+
+        let client = task::spawn(async move {
+            task::sleep(Duration::from_millis(100)).await;
+            let stream = TcpStream::connect(("localhost", port)).await?;
+            let url = format!("ws://localhost:{}/echo", port);
+            let (mut ws, res) = async_tungstenite::client_async(url, stream).await?;
+            assert_eq!(res.status(), 101);
+            assert!(res.headers().get("sec-websocket-protocol").is_none());
+
+            ws.send(Message::Text("hello".into())).await?;
+            assert_eq!(ws.next().await.unwrap()?, Message::Text("hello".into()));
+
+            ws.send(Message::Binary(vec![1, 2, 3])).await?;
+            assert_eq!(ws.next().await.unwrap()?, Message::Binary(vec![1, 2, 3]));
+
+            ws.send(Message::Ping(vec![4])).await?;
+            assert_eq!(ws.next().await.unwrap()?, Message::Pong(vec![4]));
+
+            ws.close(None).await?;
+            Ok(())
+        });
+
+        server.race(client).await
+    })
+}
+
+#[test]
+fn websocket_subprotocol() -> tide::Result<()> {
+    task::block_on(async {
+        let port = test_utils::find_port().await;
+        let server = task::spawn(async move {
+            echo_app().listen(("localhost", port)).await?;
+            tide::Result::Ok(())
+        });
+
+        let client = task::spawn(async move {
+            task::sleep(Duration::from_millis(100)).await;
+            let stream = TcpStream::connect(("localhost", port)).await?;
+            let mut req = format!("ws://localhost:{}/echo", port).into_client_request()?;
+            req.headers_mut().insert(
+                "Sec-WebSocket-Protocol",
+                HeaderValue::from_static("unknown, superchat"),
+            );
+            let (mut ws, res) = async_tungstenite::client_async(req, stream).await?;
+            assert_eq!(res.headers()["sec-websocket-protocol"], "superchat");
+            assert_eq!(ws.next().await.unwrap()?, Message::Text("superchat".into()));
+            Ok(())
+        });
+
+        server.race(client).await
+    })
+}
+
+#[async_std::test]
+async fn websocket_requires_upgrade() -> tide::Result<()> {
+    let app = echo_app();
+
+    let res: tide::http::Response = app
+        .respond(tide::http::Request::get("http://localhost/echo"))
+        .await?;
+    assert_eq!(res.status(), 426);
+    assert_eq!(res["upgrade"], "websocket");
+
+    let mut req = tide::http::Request::get("http://localhost/echo");
+    req.insert_header("Upgrade", "websocket");
+    req.insert_header("Connection", "keep-alive, Upgrade");
+    req.insert_header("Sec-WebSocket-Version", "13");
+    let res: tide::http::Response = app.respond(req).await?;
+    assert_eq!(res.status(), 400);
+
+    let mut req = tide::http::Request::get("http://localhost/echo");
+    req.insert_header("Upgrade", "websocket");
+    req.insert_header("Connection", "Upgrade");
+    req.insert_header("Sec-WebSocket-Version", "8");
+    req.insert_header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==");
+    let res: tide::http::Response = app.respond(req).await?;
+    assert_eq!(res.status(), 426);
+    assert_eq!(res["sec-websocket-version"], "13");
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn websocket_middleware_passes_through() -> tide::Result<()> {
+    let mut app = tide::new();
+    app.at("/")
+        .with(WebSocket::new(|_req, _conn| async { Ok(()) }))
+        .get(|_| async { Ok("plain http") });
+
+    let mut res: tide::http::Response = app
+        .respond(tide::http::Request::get("http://localhost/"))
+        .await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body_string().await?, "plain http");
+
+    let mut req = tide::http::Request::get("http://localhost/");
+    req.insert_header("Upgrade", "websocket");
+    req.insert_header("Connection", "Upgrade");
+    req.insert_header("Sec-WebSocket-Version", "13");
+    req.insert_header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==");
+    let res: tide::http::Response = app.respond(req).await?;
+    assert_eq!(res.status(), 101);
+    assert_eq!(res["sec-websocket-accept"], "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn websocket_unix() -> tide::Result<()> {
+    use async_std::os::unix::net::UnixStream;
+
+    task::block_on(async {
+        let tmp_dir = tempfile::tempdir()?;
+        let sock_path = tmp_dir.path().join("sock");
+        let sock_path_for_client = sock_path.clone();
+
+        let server = task::spawn(async move {
+            echo_app().listen(sock_path).await?;
+            tide::Result::Ok(())
+        });
+
+        let client = task::spawn(async move {
+            task::sleep(Duration::from_millis(100)).await;
+            let stream = UnixStream::connect(&sock_path_for_client).await?;
+            let (mut ws, _) =
+                async_tungstenite::client_async("ws://local.socket/echo", stream).await?;
+            ws.send(Message::Text("over unix".into())).await?;
+            assert_eq!(ws.next().await.unwrap()?, Message::Text("over unix".into()));
+            Ok(())
+        });
+
+        server.race(client).await
+    })
+}